@@ -5,7 +5,9 @@
 //
 
 use std::ffi::{CStr, CString};
+use std::future::Future;
 use std::os::raw::{c_char, c_void};
+use std::time::Duration;
 
 use proto::northbound_client::NorthboundClient;
 use yang3::data::{
@@ -13,9 +15,9 @@ use yang3::data::{
 };
 use yang3::ffi;
 
-use crate::client::{Client, DataType, DataValue};
+use crate::client::{Client, ClientOptions, DataType, DataValue};
 use crate::error::Error;
-use crate::YANG_MODULES_DIR;
+use crate::{interrupt, YANG_MODULES_DIR};
 
 pub mod proto {
     tonic::include_proto!("holo");
@@ -23,62 +25,177 @@ pub mod proto {
 
 type StdError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
+// HTTP/2 keepalive parameters, used to detect dead connections.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(10);
+const KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(20);
+
+// Reconnection parameters. The delay between attempts doubles after each
+// failure, up to the maximum.
+const RECONNECT_ATTEMPTS: u32 = 6;
+const RECONNECT_DELAY_MIN: Duration = Duration::from_millis(500);
+const RECONNECT_DELAY_MAX: Duration = Duration::from_secs(8);
+
+type Channel = tonic::transport::Channel;
+
 // The order of the fields in this struct is important. They must be ordered
 // such that when `Client` is dropped the client is dropped before the runtime.
 // Not doing this will result in a deadlock when dropped. Rust drops struct
 // fields in declaration order.
 #[derive(Debug)]
 pub struct GrpcClient {
-    client: NorthboundClient<tonic::transport::Channel>,
+    client: NorthboundClient<Channel>,
     runtime: tokio::runtime::Runtime,
+    dest: &'static str,
+    options: ClientOptions,
+    // YANG modules supported by the daemon, as loaded in the YANG context.
+    modules: Vec<proto::ModuleData>,
+    // Whether the connection was reestablished since last checked.
+    reconnected: bool,
 }
 
 // ===== impl GrpcClient =====
 
 impl GrpcClient {
-    fn rpc_sync_capabilities(
+    // Sends a request to holod. When the connection is lost, it's
+    // reestablished and idempotent requests are sent again.
+    fn rpc_sync<T, F, Fut>(
         &mut self,
-    ) -> Result<tonic::Response<proto::CapabilitiesResponse>, tonic::Status>
+        idempotent: bool,
+        rpc: F,
+    ) -> Result<tonic::Response<T>, Error>
+    where
+        F: Fn(NorthboundClient<Channel>) -> Fut,
+        Fut: Future<Output = Result<tonic::Response<T>, tonic::Status>>,
     {
-        let request = tonic::Request::new(proto::CapabilitiesRequest {});
-        self.runtime.block_on(self.client.capabilities(request))
+        // Non-idempotent requests (commits) can't be interrupted, as their
+        // outcome would be unknown. They get a separate, longer timeout so that
+        // a hung daemon doesn't block the CLI forever.
+        let timeout = match idempotent {
+            true => self.options.request_timeout,
+            false => self.options.commit_timeout,
+        };
+        let result = block_on(
+            &self.runtime,
+            timeout,
+            idempotent,
+            rpc(self.client.clone()),
+        )
+        .map_err(|error| match error {
+            Error::Timeout(timeout) if !idempotent => {
+                Error::CommitTimeout(timeout)
+            }
+            error => error,
+        });
+        match result {
+            Err(Error::Backend(status))
+                if status.code() == tonic::Code::Unavailable =>
+            {
+                self.reconnect()?;
+                if !idempotent {
+                    return Err(Error::Backend(status));
+                }
+                block_on(&self.runtime, timeout, true, rpc(self.client.clone()))
+            }
+            result => result,
+        }
+    }
+
+    // Reestablishes the connection to holod, retrying with exponential
+    // backoff.
+    fn reconnect(&mut self) -> Result<(), Error> {
+        let mut delay = RECONNECT_DELAY_MIN;
+        let mut attempt = 1;
+        loop {
+            eprintln!(
+                "% connection to holod lost, reconnecting (attempt {}/{})",
+                attempt, RECONNECT_ATTEMPTS
+            );
+            match self.runtime.block_on(connect(self.dest, self.options)) {
+                Ok(client) => {
+                    self.client = client;
+                    self.reconnected = true;
+                    return Ok(());
+                }
+                Err(error) if attempt == RECONNECT_ATTEMPTS => {
+                    return Err(error);
+                }
+                Err(_) => {
+                    block_on(&self.runtime, None, true, async {
+                        tokio::time::sleep(delay).await;
+                        Ok(())
+                    })?;
+                    delay = (delay * 2).min(RECONNECT_DELAY_MAX);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn rpc_sync_capabilities(
+        &mut self,
+    ) -> Result<tonic::Response<proto::CapabilitiesResponse>, Error> {
+        self.rpc_sync(true, |mut client| async move {
+            let request = tonic::Request::new(proto::CapabilitiesRequest {});
+            client.capabilities(request).await
+        })
     }
 
     fn rpc_sync_get_schema(
         &mut self,
         request: proto::GetSchemaRequest,
-    ) -> Result<tonic::Response<proto::GetSchemaResponse>, tonic::Status> {
-        let request = tonic::Request::new(request);
-        self.runtime.block_on(self.client.get_schema(request))
+    ) -> Result<tonic::Response<proto::GetSchemaResponse>, Error> {
+        self.rpc_sync(true, |mut client| {
+            let request = tonic::Request::new(request.clone());
+            async move { client.get_schema(request).await }
+        })
     }
 
     fn rpc_sync_get(
         &mut self,
         request: proto::GetRequest,
-    ) -> Result<tonic::Response<proto::GetResponse>, tonic::Status> {
-        let request = tonic::Request::new(request);
-        self.runtime.block_on(self.client.get(request))
+    ) -> Result<tonic::Response<proto::GetResponse>, Error> {
+        self.rpc_sync(true, |mut client| {
+            let request = tonic::Request::new(request.clone());
+            async move { client.get(request).await }
+        })
+    }
+
+    fn rpc_sync_get_transaction(
+        &mut self,
+        request: proto::GetTransactionRequest,
+    ) -> Result<tonic::Response<proto::GetTransactionResponse>, Error> {
+        self.rpc_sync(true, |mut client| {
+            let request = tonic::Request::new(request.clone());
+            async move { client.get_transaction(request).await }
+        })
     }
 
     fn rpc_sync_commit(
         &mut self,
         request: proto::CommitRequest,
-    ) -> Result<tonic::Response<proto::CommitResponse>, tonic::Status> {
-        let request = tonic::Request::new(request);
-        self.runtime.block_on(self.client.commit(request))
+    ) -> Result<tonic::Response<proto::CommitResponse>, Error> {
+        self.rpc_sync(false, |mut client| {
+            let request = tonic::Request::new(request.clone());
+            async move { client.commit(request).await }
+        })
     }
 
     fn rpc_sync_validate(
         &mut self,
         request: proto::ValidateRequest,
-    ) -> Result<tonic::Response<proto::ValidateResponse>, tonic::Status> {
-        let request = tonic::Request::new(request);
-        self.runtime.block_on(self.client.validate(request))
+    ) -> Result<tonic::Response<proto::ValidateResponse>, Error> {
+        self.rpc_sync(true, |mut client| {
+            let request = tonic::Request::new(request.clone());
+            async move { client.validate(request).await }
+        })
     }
 }
 
 impl Client for GrpcClient {
-    fn connect(dest: &'static str) -> Result<Self, StdError> {
+    fn connect(
+        dest: &'static str,
+        options: ClientOptions,
+    ) -> Result<Self, StdError> {
         // Initialize tokio runtime.
         let runtime = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -86,12 +203,16 @@ impl Client for GrpcClient {
             .expect("Failed to obtain a new runtime object");
 
         // Connect to holod.
-        let client = runtime
-            .block_on(NorthboundClient::connect(dest))?
-            .max_encoding_message_size(usize::MAX)
-            .max_decoding_message_size(usize::MAX);
-
-        Ok(GrpcClient { client, runtime })
+        let client = runtime.block_on(connect(dest, options))?;
+
+        Ok(GrpcClient {
+            client,
+            runtime,
+            dest,
+            options,
+            modules: Default::default(),
+            reconnected: false,
+        })
     }
 
     fn load_modules(
@@ -106,7 +227,8 @@ impl Client for GrpcClient {
 
         // Establish a separate connection to holod for libyang to fetch any
         // missing YANG modules or submodules using the `GetSchema` RPC.
-        let client = Self::connect(dest).expect("Connection to holod failed");
+        let client = Self::connect(dest, self.options)
+            .expect("Connection to holod failed");
         unsafe {
             yang_ctx.set_module_import_callback(
                 ly_module_import_cb,
@@ -115,7 +237,8 @@ impl Client for GrpcClient {
         };
 
         // Load YANG modules dynamically.
-        for module in capabilities.into_inner().supported_modules {
+        let modules = capabilities.into_inner().supported_modules;
+        for module in &modules {
             let revision = if module.revision.is_empty() {
                 None
             } else {
@@ -135,6 +258,21 @@ impl Client for GrpcClient {
                 );
             }
         }
+        self.modules = modules;
+    }
+
+    fn reconnected(&mut self) -> bool {
+        std::mem::take(&mut self.reconnected)
+    }
+
+    fn modules_changed(&mut self) -> Result<bool, Error> {
+        let capabilities = self.rpc_sync_capabilities()?;
+        Ok(capabilities.into_inner().supported_modules != self.modules)
+    }
+
+    fn version(&mut self) -> Result<String, Error> {
+        let capabilities = self.rpc_sync_capabilities()?;
+        Ok(capabilities.into_inner().version)
     }
 
     fn get(
@@ -150,20 +288,27 @@ impl Client for GrpcClient {
                 encoding: proto::Encoding::from(format) as i32,
                 with_defaults,
                 path: xpath.unwrap_or_default(),
-            })
-            .map_err(Error::Backend)?
+            })?
             .into_inner()
             .data
             .unwrap();
-        let data = match data.data.unwrap() {
-            proto::data_tree::Data::DataString(string) => {
-                DataValue::String(string)
-            }
-            proto::data_tree::Data::DataBytes(bytes) => {
-                DataValue::Binary(bytes)
-            }
-        };
-        Ok(data)
+        Ok(data.into())
+    }
+
+    fn get_transaction(
+        &mut self,
+        transaction_id: u32,
+        format: DataFormat,
+    ) -> Result<DataValue, Error> {
+        let data = self
+            .rpc_sync_get_transaction(proto::GetTransactionRequest {
+                transaction_id,
+                encoding: proto::Encoding::from(format) as i32,
+            })?
+            .into_inner()
+            .config
+            .unwrap();
+        Ok(data.into())
     }
 
     fn validate_candidate(
@@ -182,8 +327,7 @@ impl Client for GrpcClient {
             })
         };
 
-        self.rpc_sync_validate(proto::ValidateRequest { config })
-            .map_err(Error::Backend)?;
+        self.rpc_sync_validate(proto::ValidateRequest { config })?;
 
         Ok(())
     }
@@ -193,6 +337,7 @@ impl Client for GrpcClient {
         running: &DataTree<'static>,
         candidate: &DataTree<'static>,
         comment: Option<String>,
+        confirmed_timeout: u32,
     ) -> Result<(), Error> {
         let operation = proto::commit_request::Operation::Change as i32;
         let config = {
@@ -214,9 +359,8 @@ impl Client for GrpcClient {
             operation,
             config,
             comment: comment.unwrap_or_default(),
-            confirmed_timeout: 0,
-        })
-        .map_err(Error::Backend)?;
+            confirmed_timeout,
+        })?;
 
         Ok(())
     }
@@ -244,8 +388,87 @@ impl From<DataFormat> for proto::Encoding {
     }
 }
 
+impl From<proto::DataTree> for DataValue {
+    fn from(data: proto::DataTree) -> DataValue {
+        match data.data.unwrap() {
+            proto::data_tree::Data::DataString(string) => {
+                DataValue::String(string)
+            }
+            proto::data_tree::Data::DataBytes(bytes) => {
+                DataValue::Binary(bytes)
+            }
+        }
+    }
+}
+
 // ===== helper functions =====
 
+// Runs the given request to completion, failing if it doesn't complete within
+// the timeout. In interactive sessions, interruptible requests are canceled
+// when Ctrl-C is pressed.
+fn block_on<T>(
+    runtime: &tokio::runtime::Runtime,
+    timeout: Option<Duration>,
+    interruptible: bool,
+    request: impl Future<Output = Result<T, tonic::Status>>,
+) -> Result<T, Error> {
+    let request = async {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, request)
+                .await
+                .map_err(|_| Error::Timeout(timeout))?
+                .map_err(Error::Backend),
+            None => request.await.map_err(Error::Backend),
+        }
+    };
+
+    runtime.block_on(async {
+        if !interruptible || !interrupt::is_enabled() {
+            return request.await;
+        }
+
+        tokio::select! {
+            result = request => result,
+            _ = tokio::signal::ctrl_c() => Err(Error::Interrupted),
+        }
+    })
+}
+
+// Establishes a connection to holod.
+async fn connect(
+    dest: &'static str,
+    options: ClientOptions,
+) -> Result<NorthboundClient<Channel>, Error> {
+    let endpoint = tonic::transport::Endpoint::from_shared(dest)
+        .map_err(|error| {
+            Error::Backend(tonic::Status::invalid_argument(error.to_string()))
+        })?
+        .connect_timeout(options.connect_timeout)
+        .http2_keep_alive_interval(KEEPALIVE_INTERVAL)
+        .keep_alive_timeout(KEEPALIVE_TIMEOUT)
+        .keep_alive_while_idle(true);
+    let channel =
+        tokio::time::timeout(options.connect_timeout, endpoint.connect())
+            .await
+            .map_err(|_| Error::Timeout(options.connect_timeout))?
+            .map_err(|error| Error::Unreachable(error_chain(&error)))?;
+    let client = NorthboundClient::new(channel)
+        .max_encoding_message_size(usize::MAX)
+        .max_decoding_message_size(usize::MAX);
+    Ok(client)
+}
+
+// Formats an error along with its sources.
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut string = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        string.push_str(&format!(": {}", error));
+        source = error.source();
+    }
+    string
+}
+
 unsafe extern "C" fn ly_module_import_cb(
     module_name: *const c_char,
     module_revision: *const c_char,
@@ -6,12 +6,25 @@
 
 pub mod grpc;
 
+use std::time::Duration;
+
 use yang3::data::{DataFormat, DataTree};
 
 use crate::error::Error;
 
 type StdError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
+// Connection parameters.
+#[derive(Clone, Copy, Debug)]
+pub struct ClientOptions {
+    // Maximum time to wait for the connection to be established.
+    pub connect_timeout: Duration,
+    // Maximum time to wait for each request to complete, if any.
+    pub request_timeout: Option<Duration>,
+    // Maximum time to wait for commits to complete, if any.
+    pub commit_timeout: Option<Duration>,
+}
+
 pub enum DataType {
     All = 0,
     Config = 1,
@@ -25,7 +38,10 @@ pub enum DataValue {
 
 pub trait Client: Send + std::fmt::Debug {
     // Connect to the Holo daemon.
-    fn connect(dest: &'static str) -> Result<Self, StdError>
+    fn connect(
+        dest: &'static str,
+        options: ClientOptions,
+    ) -> Result<Self, StdError>
     where
         Self: Sized;
 
@@ -36,6 +52,16 @@ pub trait Client: Send + std::fmt::Debug {
         yang_ctx: &mut yang3::context::Context,
     );
 
+    // Check whether the connection was reestablished since the last call.
+    fn reconnected(&mut self) -> bool;
+
+    // Check whether the set of supported YANG modules changed since they were
+    // loaded.
+    fn modules_changed(&mut self) -> Result<bool, Error>;
+
+    // Retrieve the version of the Holo daemon.
+    fn version(&mut self) -> Result<String, Error>;
+
     // Retrieve configuration data, state data or both.
     fn get(
         &mut self,
@@ -45,6 +71,13 @@ pub trait Client: Send + std::fmt::Debug {
         xpath: Option<String>,
     ) -> Result<DataValue, Error>;
 
+    // Retrieve the configuration of a transaction from the rollback log.
+    fn get_transaction(
+        &mut self,
+        transaction_id: u32,
+        format: DataFormat,
+    ) -> Result<DataValue, Error>;
+
     // Validate the provided candidate configuration.
     fn validate_candidate(
         &mut self,
@@ -57,6 +90,7 @@ pub trait Client: Send + std::fmt::Debug {
         running: &DataTree<'static>,
         candidate: &DataTree<'static>,
         comment: Option<String>,
+        confirmed_timeout: u32,
     ) -> Result<(), Error>;
 }
 
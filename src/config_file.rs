@@ -0,0 +1,338 @@
+//
+// Copyright (c) The Holo Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+use crate::error::{Error, ParserError};
+use crate::session::Session;
+use crate::token::Commands;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConfigFileFormat {
+    // Indented list of commands, as displayed by "show running".
+    Cli,
+    // Hierarchical format, where containers and list entries are displayed as
+    // blocks delimited by curly braces.
+    Hierarchical,
+    // Flat format, where each line is a "set" command starting from the
+    // configuration root.
+    Set,
+}
+
+// Configuration command along with its line number in the file.
+pub type ConfigFileCommand = (usize, String);
+
+// ===== impl ConfigFileFormat =====
+
+impl ConfigFileFormat {
+    pub(crate) fn detect(data: &str) -> ConfigFileFormat {
+        if data
+            .lines()
+            .filter(|line| !is_comment(line))
+            .any(|line| line.trim_end().ends_with('{'))
+        {
+            ConfigFileFormat::Hierarchical
+        } else if data
+            .lines()
+            .filter(|line| !is_comment(line) && !line.trim().is_empty())
+            .all(|line| line.trim_start().starts_with("set "))
+        {
+            ConfigFileFormat::Set
+        } else {
+            ConfigFileFormat::Cli
+        }
+    }
+}
+
+// ===== global functions =====
+
+// Splits the contents of a configuration file into a list of commands.
+pub(crate) fn parse(
+    data: &str,
+    format: ConfigFileFormat,
+) -> Result<Vec<ConfigFileCommand>, (usize, ParserError)> {
+    match format {
+        ConfigFileFormat::Cli => Ok(data
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.to_owned()))
+            .collect()),
+        ConfigFileFormat::Hierarchical => parse_hierarchical(data),
+        ConfigFileFormat::Set => Ok(data
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !is_comment(line))
+            .filter_map(|(index, line)| {
+                let line = line.trim_start().strip_prefix("set ")?;
+                Some((index + 1, line.to_owned()))
+            })
+            .collect()),
+    }
+}
+
+// Loads the contents of a configuration file into the candidate
+// configuration.
+//
+// All commands are processed, even after a failure. The returned list
+// contains the errors found along with their line numbers.
+pub(crate) fn load(
+    commands: &Commands,
+    session: &mut Session,
+    data: &str,
+) -> Vec<(usize, Error)> {
+    let format = ConfigFileFormat::detect(data);
+    let cmds = match parse(data, format) {
+        Ok(cmds) => cmds,
+        Err((line, error)) => return vec![(line, Error::Parser(error))],
+    };
+
+    let mut errors = vec![];
+    let mode = session.mode().clone();
+    for (line, cmd) in cmds {
+        // Commands extracted from hierarchical and flat files are relative
+        // to the CLI node where loading started.
+        if format != ConfigFileFormat::Cli {
+            session.mode_set(mode.clone());
+        }
+
+        if let Err(error) = crate::enter_command(commands, session, &cmd) {
+            errors.push((line, error));
+        }
+    }
+
+    errors
+}
+
+// ===== helper functions =====
+
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with('!') || line.starts_with('#')
+}
+
+// Converts a hierarchical configuration into a list of full commands, each one
+// starting from the configuration root.
+//
+// Statements terminated by a semicolon are converted into commands prefixed by
+// the words of all enclosing blocks. Empty blocks are converted into commands
+// as well, so that list entries and presence containers without children
+// aren't lost. Quoted values can contain whitespace and delimiters.
+fn parse_hierarchical(
+    data: &str,
+) -> Result<Vec<ConfigFileCommand>, (usize, ParserError)> {
+    struct Block {
+        words: Vec<String>,
+        line: usize,
+        empty: bool,
+    }
+
+    let mut cmds = vec![];
+    let mut blocks: Vec<Block> = vec![];
+    let mut words: Vec<String> = vec![];
+    let mut words_line = 0;
+    let mut line = 0;
+
+    let prefix = |blocks: &[Block]| {
+        blocks
+            .iter()
+            .flat_map(|block| block.words.iter().cloned())
+            .collect::<Vec<_>>()
+    };
+
+    for (index, text) in data.lines().enumerate() {
+        line = index + 1;
+        if is_comment(text) {
+            continue;
+        }
+
+        let Some(line_words) = split_line(text) else {
+            return Err((line, ParserError::UnexpectedEnd));
+        };
+        for word in line_words {
+            match word.as_str() {
+                ";" => {
+                    if words.is_empty() {
+                        continue;
+                    }
+                    if let Some(block) = blocks.last_mut() {
+                        block.empty = false;
+                    }
+                    let mut cmd = prefix(&blocks);
+                    cmd.append(&mut words);
+                    cmds.push((words_line, cmd.join(" ")));
+                }
+                "{" => {
+                    if words.is_empty() {
+                        return Err((line, ParserError::Unexpected('{')));
+                    }
+                    if let Some(block) = blocks.last_mut() {
+                        block.empty = false;
+                    }
+                    blocks.push(Block {
+                        words: std::mem::take(&mut words),
+                        line: words_line,
+                        empty: true,
+                    });
+                }
+                "}" => {
+                    if !words.is_empty() {
+                        return Err((line, ParserError::Unexpected('}')));
+                    }
+                    let Some(block) = blocks.pop() else {
+                        return Err((line, ParserError::Unexpected('}')));
+                    };
+                    if block.empty {
+                        let mut cmd = prefix(&blocks);
+                        cmd.extend(block.words);
+                        cmds.push((block.line, cmd.join(" ")));
+                    }
+                }
+                _ => {
+                    if words.is_empty() {
+                        words_line = line;
+                    }
+                    words.push(word);
+                }
+            }
+        }
+    }
+
+    // Check for unterminated statements or blocks.
+    if !words.is_empty() || !blocks.is_empty() {
+        return Err((line, ParserError::UnexpectedEnd));
+    }
+
+    Ok(cmds)
+}
+
+// Splits a line of a hierarchical configuration into words, handling
+// delimiters attached to them. Quoted strings are kept as is, so that they're
+// interpreted by the command parser. Returns `None` if a quoted string isn't
+// terminated.
+fn split_line(text: &str) -> Option<Vec<String>> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' | ';' => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                words.push(c.to_string());
+            }
+            _ if c.is_whitespace() => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            '"' => {
+                word.push(c);
+                loop {
+                    let c = chars.next()?;
+                    word.push(c);
+                    match c {
+                        '"' => break,
+                        '\\' => word.push(chars.next()?),
+                        _ => (),
+                    }
+                }
+            }
+            _ => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    Some(words)
+}
+
+// ===== unit tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hierarchical_blocks() {
+        let data = "\
+interfaces {
+    interface eth0 {
+        type iana-if-type:ethernetCsmacd;
+        enabled true;
+    }
+    interface lo {
+    }
+}
+";
+        assert_eq!(
+            parse_hierarchical(data).unwrap(),
+            [
+                (
+                    3,
+                    "interfaces interface eth0 type iana-if-type:ethernetCsmacd"
+                        .to_owned()
+                ),
+                (4, "interfaces interface eth0 enabled true".to_owned()),
+                (6, "interfaces interface lo".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn hierarchical_attached_delimiters() {
+        let data = "! comment\nsystem{hostname r1;contact x;}\n";
+        assert_eq!(
+            parse_hierarchical(data).unwrap(),
+            [
+                (2, "system hostname r1".to_owned()),
+                (2, "system contact x".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn hierarchical_quoted_values() {
+        let data = "\
+interfaces {
+    interface eth0 {
+        description \"uplink {to r2}; \\\"core\\\"\";
+    }
+}
+";
+        let parsed = parse_hierarchical(data).unwrap();
+        assert_eq!(
+            parsed,
+            [(
+                3,
+                "interfaces interface eth0 description \"uplink {to r2}; \\\"core\\\"\""
+                    .to_owned()
+            )]
+        );
+
+        // The quoted value is a single word for the command parser.
+        let words = crate::parser::split_words(&parsed[0].1);
+        assert_eq!(words.last().unwrap(), "uplink {to r2}; \"core\"");
+    }
+
+    #[test]
+    fn hierarchical_errors() {
+        assert!(matches!(
+            parse_hierarchical("{\n"),
+            Err((1, ParserError::Unexpected('{')))
+        ));
+        assert!(matches!(
+            parse_hierarchical("system {\n}\n}\n"),
+            Err((3, ParserError::Unexpected('}')))
+        ));
+        assert!(matches!(
+            parse_hierarchical("system {\n  hostname r1;\n"),
+            Err((2, ParserError::UnexpectedEnd))
+        ));
+        assert!(matches!(
+            parse_hierarchical("system {\n  contact \"x;\n}\n"),
+            Err((2, ParserError::UnexpectedEnd))
+        ));
+    }
+}
@@ -4,6 +4,8 @@
 // SPDX-License-Identifier: MIT
 //
 
+use std::time::Duration;
+
 use indextree::NodeId;
 
 #[derive(Debug)]
@@ -13,6 +15,10 @@ pub enum Error {
     ValidateConfig(yang3::Error),
     Callback(String),
     Backend(tonic::Status),
+    Unreachable(String),
+    Timeout(Duration),
+    CommitTimeout(Duration),
+    Interrupted,
 }
 
 #[derive(Debug)]
@@ -20,6 +26,8 @@ pub enum ParserError {
     NoMatch,
     Incomplete(NodeId),
     Ambiguous(Vec<NodeId>),
+    Unexpected(char),
+    UnexpectedEnd,
 }
 
 // ===== impl Error =====
@@ -40,12 +48,33 @@ impl std::fmt::Display for Error {
             Error::Backend(error) => {
                 write!(f, "{}", error)
             }
+            Error::Unreachable(error) => {
+                write!(f, "holod is unreachable: {}", error)
+            }
+            Error::Timeout(timeout) => write!(
+                f,
+                "holod isn't responding: timed out after {} s",
+                timeout.as_secs()
+            ),
+            Error::CommitTimeout(timeout) => write!(
+                f,
+                "holod isn't responding: commit timed out after {} s, \
+                 its outcome is unknown, check the running configuration",
+                timeout.as_secs()
+            ),
+            Error::Interrupted => write!(f, "interrupted"),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+impl From<String> for Error {
+    fn from(error: String) -> Error {
+        Error::Callback(error)
+    }
+}
+
 // ===== impl ParserError =====
 
 impl std::fmt::Display for ParserError {
@@ -54,6 +83,8 @@ impl std::fmt::Display for ParserError {
             ParserError::NoMatch => write!(f, "unknown command"),
             ParserError::Incomplete(_) => write!(f, "incomplete command"),
             ParserError::Ambiguous(_) => write!(f, "ambiguous command"),
+            ParserError::Unexpected(c) => write!(f, "unexpected '{}'", c),
+            ParserError::UnexpectedEnd => write!(f, "unexpected end of input"),
         }
     }
 }
@@ -6,20 +6,28 @@
 
 use std::collections::BTreeMap;
 use std::fmt::Write;
+use std::io::IsTerminal;
 use std::process::{Child, Command, Stdio};
 
 use indextree::NodeId;
-use prettytable::{format, row, Table};
+use nu_ansi_term::{Color, Style};
+use prettytable::{format, row, Cell, Row, Table};
 use similar::TextDiff;
 use yang3::data::{
-    Data, DataFormat, DataNodeRef, DataParserFlags, DataPrinterFlags, DataTree,
-    DataValidationFlags,
+    Data, DataDiffFlags, DataDiffOp, DataFormat, DataNodeRef, DataParserFlags,
+    DataPrinterFlags, DataTree, DataValidationFlags,
 };
 use yang3::schema::SchemaNodeKind;
 
 use crate::client::{DataType, DataValue};
-use crate::parser::ParsedArgs;
-use crate::session::{CommandMode, ConfigurationType, Session};
+use crate::config_file;
+use crate::error::Error;
+use crate::interrupt;
+use crate::pager;
+use crate::parser::{self, ParsedArgs};
+use crate::session::{path_predicate, CommandMode, ConfigurationType, Session};
+use crate::template;
+use crate::terminal;
 use crate::token::{Commands, TokenKind};
 use crate::YANG_CTX;
 
@@ -48,6 +56,18 @@ enum YangValueDisplayFormat {
     Hex32,
 }
 
+// Kinds of text that are colorized in the command output.
+#[derive(Clone, Copy)]
+enum OutputStyle {
+    Keyword,
+    Value,
+    ListHeader,
+    DiffHeader,
+    DiffHunk,
+    DiffAdd,
+    DiffRemove,
+}
+
 // ===== impl YangTableBuilder =====
 
 impl<'a> YangTableBuilder<'a> {
@@ -163,6 +183,11 @@ impl<'a> YangTableBuilder<'a> {
         };
 
         for dnode in dnode.find_xpath(xpath).unwrap() {
+            // Stop rendering large tables when interrupted.
+            if interrupt::is_interrupted() {
+                return;
+            }
+
             let mut values = values.clone();
             for column in columns {
                 let value = match &column.value {
@@ -211,14 +236,18 @@ impl<'a> YangTableBuilder<'a> {
             .flat_map(|(_, columns)| columns.iter())
             .map(|column| column.title)
             .collect();
-        table.set_titles(column_titles.into());
 
         // Populate the table with data from the specified paths.
         let values = Vec::new();
         Self::show_path(&mut table, dnode, &self.paths, values);
+        if interrupt::is_interrupted() {
+            return Err("interrupted".to_owned());
+        }
 
         // Print the table to stdout.
-        if let Err(error) = page_table(self.session, &table) {
+        if let Err(error) =
+            page_table(self.session, &mut table, column_titles.into())
+        {
             println!("% failed to display data: {}", error);
         }
 
@@ -226,6 +255,31 @@ impl<'a> YangTableBuilder<'a> {
     }
 }
 
+// ===== impl OutputStyle =====
+
+impl OutputStyle {
+    fn style(&self) -> Style {
+        match self {
+            OutputStyle::Keyword => Style::new(),
+            OutputStyle::Value => Style::new().fg(Color::Cyan),
+            OutputStyle::ListHeader => Style::new().fg(Color::Green).bold(),
+            OutputStyle::DiffHeader => Style::new().bold(),
+            OutputStyle::DiffHunk => Style::new().fg(Color::Cyan),
+            OutputStyle::DiffAdd => Style::new().fg(Color::Green),
+            OutputStyle::DiffRemove => Style::new().fg(Color::Red),
+        }
+    }
+
+    // Returns the text styled, or unchanged if colors are disabled.
+    fn paint(&self, colors: bool, text: &str) -> String {
+        if colors {
+            self.style().paint(text).to_string()
+        } else {
+            text.to_owned()
+        }
+    }
+}
+
 // ===== helper functions =====
 
 fn get_arg(args: &mut ParsedArgs, name: &str) -> String {
@@ -241,64 +295,205 @@ fn get_opt_arg(args: &mut ParsedArgs, name: &str) -> Option<String> {
     None
 }
 
-fn pager() -> Result<Child, std::io::Error> {
-    Command::new("less")
-        // Exit immediately if the data fits on one screen.
-        .arg("-F")
-        // Do not clear the screen on exit.
-        .arg("-X")
-        .stdin(Stdio::piped())
-        .spawn()
+// Checks whether the command output should be colorized.
+fn use_colors(session: &Session) -> bool {
+    session.settings().colors() && std::io::stdout().is_terminal()
 }
 
-fn page_output(session: &Session, data: &str) -> Result<(), std::io::Error> {
-    if session.use_pager() {
-        use std::io::Write;
-
-        // Spawn the pager process.
-        let mut pager = pager()?;
+fn pager(command: &str) -> Result<Child, std::io::Error> {
+    let mut args = command.split_whitespace();
+    let program = args.next().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "empty pager command",
+        )
+    })?;
+    let mut command = Command::new(program);
+    command.args(args).stdin(Stdio::piped());
 
-        // Feed the data to the pager.
-        pager.stdin.as_mut().unwrap().write_all(data.as_bytes())?;
+    // Let "less" display colors, unless configured otherwise.
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    command.spawn()
+}
 
-        // Wait for the pager process to finish.
-        pager.wait()?;
-    } else {
+fn page_output(session: &Session, data: &str) -> Result<(), std::io::Error> {
+    if !session.use_pager() {
         // Print the data directly to the console.
         println!("{}", data);
+        return Ok(());
+    }
+
+    match session.settings().pager_command() {
+        Some(command) => {
+            use std::io::Write;
+
+            // Spawn the pager process.
+            let mut pager = pager(command)?;
+
+            // Feed the data to the pager.
+            pager.stdin.as_mut().unwrap().write_all(data.as_bytes())?;
+
+            // Wait for the pager process to finish.
+            pager.wait()?;
+        }
+        None => pager::page(data)?,
     }
 
     Ok(())
 }
 
-fn page_table(session: &Session, table: &Table) -> Result<(), std::io::Error> {
+// Displays the table with the given titles. The titles are set here because
+// prettytable doesn't provide access to them once set.
+fn page_table(
+    session: &Session,
+    table: &mut Table,
+    mut titles: Row,
+) -> Result<(), std::io::Error> {
     if table.is_empty() {
         return Ok(());
     }
 
-    if session.use_pager() {
-        use std::io::Write;
+    // Fit the table to the terminal width.
+    if std::io::stdout().is_terminal() {
+        let (columns, _) = terminal::terminal_size();
+        fit_table(table, &mut titles, columns);
+    }
+    table.set_titles(titles);
 
-        // Spawn the pager process.
-        let mut pager = pager()?;
+    // Print the table.
+    let mut output = Vec::new();
+    table.print(&mut output)?;
+    page_output(session, &String::from_utf8_lossy(&output))
+}
 
-        // Print the table.
-        let mut output = Vec::new();
-        table.print(&mut output)?;
-        writeln!(output)?;
+// Shrinks the widest columns of the table until it fits in the given width,
+// wrapping the contents of the cells (titles included) that no longer fit.
+fn fit_table(table: &mut Table, titles: &mut Row, width: usize) {
+    const MIN_COLUMN_WIDTH: usize = 8;
+
+    let num_columns = table
+        .row_iter()
+        .map(Row::len)
+        .chain(std::iter::once(titles.len()))
+        .max()
+        .unwrap_or(0);
+    if num_columns == 0 {
+        return;
+    }
 
-        // Feed the data to the pager.
-        pager.stdin.as_mut().unwrap().write_all(&output)?;
+    // Compute the current width of each column.
+    let mut widths = vec![0; num_columns];
+    for row in table.row_iter().chain(std::iter::once(&*titles)) {
+        for (cell, width) in row.iter().zip(widths.iter_mut()) {
+            let cell_width = cell
+                .get_content()
+                .lines()
+                .map(terminal::display_width)
+                .max()
+                .unwrap_or(0);
+            *width = (*width).max(cell_width);
+        }
+    }
 
-        // Wait for the pager process to finish.
-        pager.wait()?;
-    } else {
-        // Print the table directly to the console.
-        table.printstd();
-        println!();
+    // Columns are surrounded by padding and separated by a single character.
+    let (left, right) = table.get_format().get_padding();
+    let available = width.saturating_sub(num_columns * (left + right + 1) - 1);
+    while widths.iter().sum::<usize>() > available {
+        let Some(widest) = widths.iter_mut().max() else {
+            break;
+        };
+        if *widest <= MIN_COLUMN_WIDTH {
+            break;
+        }
+        *widest -= 1;
     }
 
-    Ok(())
+    // Wrap the cells exceeding the new column widths.
+    for row in table.row_iter_mut().chain(std::iter::once(titles)) {
+        for (cell, width) in row.iter_mut().zip(widths.iter()) {
+            let content = cell.get_content();
+            if content
+                .lines()
+                .any(|line| terminal::display_width(line) > *width)
+            {
+                *cell = Cell::new(&wrap_text(&content, *width));
+            }
+        }
+    }
+}
+
+// Wraps each line of the text at word boundaries so that it doesn't exceed
+// the given width. Words longer than the width are split.
+fn wrap_text(text: &str, width: usize) -> String {
+    let mut lines = vec![];
+    for line in text.lines() {
+        let mut current = String::new();
+        for mut word in line.split(' ') {
+            loop {
+                let current_len = current.chars().count();
+                let word_len = word.chars().count();
+                if current.is_empty() && word_len <= width {
+                    current.push_str(word);
+                    break;
+                }
+                if !current.is_empty() && current_len + 1 + word_len <= width {
+                    current.push(' ');
+                    current.push_str(word);
+                    break;
+                }
+                if !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                    continue;
+                }
+
+                // The word alone doesn't fit, so split it.
+                let split = word
+                    .char_indices()
+                    .nth(width)
+                    .map_or(word.len(), |(idx, _)| idx);
+                lines.push(word[..split].to_owned());
+                word = &word[split..];
+            }
+        }
+        lines.push(current);
+    }
+    lines.join("\n")
+}
+
+// Reads the command history of the given CLI mode.
+fn read_history(session: &Session, configure: bool) -> Vec<String> {
+    session
+        .history_path(configure)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|data| data.lines().map(str::to_owned).collect())
+        .unwrap_or_default()
+}
+
+// Writes data to a file atomically, by writing it to a temporary file first
+// and then renaming it to the final path. The file is readable only by its
+// owner, as configurations may contain sensitive data (e.g. passwords).
+fn write_file_atomic(path: &str, data: &str) -> Result<(), std::io::Error> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let tmp_path = format!("{}.tmp.{}", path, std::process::id());
+    let result = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(data.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&tmp_path, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+
+    result
 }
 
 fn fetch_data(
@@ -321,6 +516,25 @@ fn fetch_data(
     .map_err(|error| format!("% failed to parse data: {}", error))
 }
 
+fn fetch_transaction(
+    session: &mut Session,
+    transaction_id: u32,
+) -> Result<DataTree<'static>, String> {
+    let yang_ctx = YANG_CTX.get().unwrap();
+    let data_format = DataFormat::LYB;
+    let data = session
+        .get_transaction(transaction_id, data_format)
+        .map_err(|error| format!("failed to fetch transaction: {}", error))?;
+    DataTree::parse_string(
+        yang_ctx,
+        data.as_bytes(),
+        data_format,
+        DataParserFlags::empty(),
+        DataValidationFlags::PRESENT | DataValidationFlags::NO_STATE,
+    )
+    .map_err(|error| format!("failed to parse transaction: {}", error))
+}
+
 // ===== impl DataNodeRef =====
 
 /// Extension methods for DataNodeRef.
@@ -360,9 +574,17 @@ pub(crate) fn cmd_config(
     _commands: &Commands,
     session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let mode = CommandMode::Configure { nodes: vec![] };
     session.mode_set(mode);
+
+    // Let the user know about uncommitted changes left by a previous session.
+    if session.candidate_autosaved() {
+        println!(
+            "% uncommitted changes from a previous session were found, use \"restore autosave\" to recover them"
+        );
+    }
+
     Ok(false)
 }
 
@@ -372,7 +594,7 @@ pub(crate) fn cmd_exit_exec(
     _commands: &Commands,
     _session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     // Do nothing.
     Ok(true)
 }
@@ -381,7 +603,7 @@ pub(crate) fn cmd_exit_config(
     _commands: &Commands,
     session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     session.mode_config_exit();
     Ok(false)
 }
@@ -392,7 +614,7 @@ pub(crate) fn cmd_end(
     _commands: &Commands,
     session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     session.mode_set(CommandMode::Operational);
     Ok(false)
 }
@@ -403,7 +625,7 @@ pub(crate) fn cmd_list(
     commands: &Commands,
     session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     match session.mode() {
         CommandMode::Operational => {
             // List EXEC-level commands.
@@ -459,7 +681,7 @@ pub(crate) fn cmd_pwd(
     _commands: &Commands,
     session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     println!(
         "{}",
         session.mode().data_path().unwrap_or_else(|| "/".to_owned())
@@ -473,26 +695,184 @@ pub(crate) fn cmd_discard(
     _commands: &Commands,
     session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     session.candidate_discard();
     Ok(false)
 }
 
+// ===== "load" =====
+
+pub(crate) fn cmd_load(
+    commands: &Commands,
+    session: &mut Session,
+    mut args: ParsedArgs,
+) -> Result<bool, Error> {
+    let path = get_arg(&mut args, "file");
+    let data = std::fs::read_to_string(&path)
+        .map_err(|error| format!("failed to read {}: {}", path, error))?;
+
+    // Load the configuration starting from the configuration root, restoring
+    // the current CLI node afterwards. All changes are recorded as a single
+    // edit.
+    let mode = session.mode().clone();
+    session.mode_set(CommandMode::Configure { nodes: vec![] });
+    session.edit_group_begin(&format!("load {}", path));
+    let errors = config_file::load(commands, session, &data);
+    session.edit_group_end(false);
+    session.mode_set(mode);
+
+    if !errors.is_empty() {
+        for (line, error) in &errors {
+            println!("% {}:{}: {}", path, line, error);
+        }
+        return Err(Error::Callback(format!(
+            "{} error(s) found in {}",
+            errors.len(),
+            path
+        )));
+    }
+    println!("% configuration loaded from {}", path);
+
+    Ok(false)
+}
+
+// ===== "apply template" =====
+
+pub(crate) fn cmd_apply_template(
+    commands: &Commands,
+    session: &mut Session,
+    mut args: ParsedArgs,
+) -> Result<bool, Error> {
+    let name = get_arg(&mut args, "name");
+    let vars = get_opt_arg(&mut args, "variables").unwrap_or_default();
+    let vars = template::parse_vars(&vars)?;
+    let data = template::read(&name)?;
+    let data = template::expand(&data, &vars)?;
+
+    // Apply the template starting from the current CLI node, restoring it
+    // afterwards. The template is applied as a whole or not at all.
+    let mode = session.mode().clone();
+    session.edit_group_begin(&format!("apply template {}", name));
+    let errors = config_file::load(commands, session, &data);
+    session.mode_set(mode);
+    session.edit_group_end(!errors.is_empty());
+
+    if !errors.is_empty() {
+        for (line, error) in &errors {
+            println!("% {}:{}: {}", name, line, error);
+        }
+        return Err(Error::Callback(format!(
+            "{} error(s) found in template {}, no changes applied",
+            errors.len(),
+            name
+        )));
+    }
+
+    Ok(false)
+}
+
+// ===== "undo" =====
+
+pub(crate) fn cmd_undo(
+    _commands: &Commands,
+    session: &mut Session,
+    mut args: ParsedArgs,
+) -> Result<bool, Error> {
+    let count =
+        match get_opt_arg(&mut args, "count") {
+            Some(count) => count
+                .parse::<usize>()
+                .ok()
+                .filter(|count| *count > 0)
+                .ok_or_else(|| format!("invalid number of edits: {}", count))?,
+            None => 1,
+        };
+    match session.candidate_undo(count) {
+        0 => return Err(Error::Callback("nothing to undo".to_owned())),
+        1 => println!("% 1 edit undone"),
+        reverted => println!("% {} edits undone", reverted),
+    }
+
+    Ok(false)
+}
+
+// ===== "redo" =====
+
+pub(crate) fn cmd_redo(
+    _commands: &Commands,
+    session: &mut Session,
+    _args: ParsedArgs,
+) -> Result<bool, Error> {
+    if !session.candidate_redo() {
+        return Err(Error::Callback("nothing to redo".to_owned()));
+    }
+    println!("% 1 edit redone");
+
+    Ok(false)
+}
+
+// ===== "checkpoint" =====
+
+pub(crate) fn cmd_checkpoint(
+    _commands: &Commands,
+    session: &mut Session,
+    mut args: ParsedArgs,
+) -> Result<bool, Error> {
+    let name = get_arg(&mut args, "name");
+    session.candidate_checkpoint(&name);
+
+    Ok(false)
+}
+
+// ===== "restore" =====
+
+pub(crate) fn cmd_restore(
+    _commands: &Commands,
+    session: &mut Session,
+    mut args: ParsedArgs,
+) -> Result<bool, Error> {
+    if get_opt_arg(&mut args, "autosave").is_some() {
+        if !session.candidate_restore_autosave() {
+            return Err(Error::Callback(
+                "no uncommitted changes to restore".to_owned(),
+            ));
+        }
+        return Ok(false);
+    }
+
+    let name = get_arg(&mut args, "name");
+    if !session.candidate_restore(&name) {
+        return Err(Error::Callback(format!("checkpoint not found: {}", name)));
+    }
+
+    Ok(false)
+}
+
 // ===== "commit" =====
 
 pub(crate) fn cmd_commit(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let comment = get_opt_arg(&mut args, "comment");
-    match session.candidate_commit(comment) {
-        Ok(_) => {
-            println!("% configuration committed successfully");
-        }
-        Err(error) => {
-            println!("% {}", error);
-        }
+    let confirmed = match get_opt_arg(&mut args, "confirmed") {
+        Some(minutes) => Some(
+            minutes
+                .parse::<u32>()
+                .ok()
+                .filter(|minutes| *minutes > 0)
+                .ok_or_else(|| format!("invalid timeout: {}", minutes))?,
+        ),
+        None => None,
+    };
+    session.candidate_commit(comment, confirmed)?;
+    match confirmed {
+        Some(minutes) => println!(
+            "% configuration committed, will be rolled back in {} minute(s) unless confirmed",
+            minutes
+        ),
+        None => println!("% configuration committed successfully"),
     }
 
     Ok(false)
@@ -504,13 +884,23 @@ pub(crate) fn cmd_validate(
     _commands: &Commands,
     session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
-    match session.candidate_validate() {
-        Ok(_) => println!("% candidate configuration validated successfully"),
-        Err(error) => {
-            println!("% {}", error)
-        }
-    }
+) -> Result<bool, Error> {
+    session.candidate_validate()?;
+    println!("% candidate configuration validated successfully");
+
+    Ok(false)
+}
+
+// ===== "set cli" =====
+
+pub(crate) fn cmd_set_cli(
+    _commands: &Commands,
+    session: &mut Session,
+    mut args: ParsedArgs,
+) -> Result<bool, Error> {
+    let option = get_arg(&mut args, "option");
+    let value = get_arg(&mut args, "value");
+    session.settings_set(&option, &value)?;
 
     Ok(false)
 }
@@ -520,22 +910,14 @@ pub(crate) fn cmd_validate(
 fn cmd_show_config_cmds(
     config: &DataTree<'static>,
     with_defaults: bool,
+    colors: bool,
 ) -> String {
     let mut output = String::new();
 
     // Iterate over data nodes that represent full commands.
     for dnode in config
         .traverse()
-        .filter(|dnode| {
-            let snode = dnode.schema();
-            match snode.kind() {
-                SchemaNodeKind::Container => !snode.is_np_container(),
-                SchemaNodeKind::Leaf => !snode.is_list_key(),
-                SchemaNodeKind::LeafList => true,
-                SchemaNodeKind::List => true,
-                _ => false,
-            }
-        })
+        .filter(is_full_command)
         .filter(|dnode| with_defaults || !dnode.is_default())
     {
         let mut tokens = vec![];
@@ -549,6 +931,13 @@ fn cmd_show_config_cmds(
             write!(indent, " ").unwrap();
         }
 
+        // List entries are highlighted as headers of their child commands.
+        let keyword = if dnode.schema().kind() == SchemaNodeKind::List {
+            OutputStyle::ListHeader
+        } else {
+            OutputStyle::Keyword
+        };
+
         // Build command line.
         for dnode in dnode
             .inclusive_ancestors()
@@ -563,12 +952,13 @@ fn cmd_show_config_cmds(
             .iter()
             .rev()
         {
-            tokens.push(dnode.schema().name().to_owned());
+            tokens.push(keyword.paint(colors, dnode.schema().name()));
             for dnode in dnode.list_keys() {
-                tokens.push(dnode.value_canonical().unwrap());
+                let value = dnode.value_canonical().unwrap();
+                tokens.push(OutputStyle::Value.paint(colors, &value));
             }
             if let Some(value) = dnode.value_canonical() {
-                tokens.push(value.clone());
+                tokens.push(OutputStyle::Value.paint(colors, &value));
             }
         }
 
@@ -585,6 +975,180 @@ fn cmd_show_config_cmds(
     output
 }
 
+fn cmd_show_config_hierarchical(
+    config: &DataTree<'static>,
+    with_defaults: bool,
+    colors: bool,
+) -> String {
+    let mut output = String::new();
+
+    if let Some(dnode) = config.reference() {
+        for dnode in dnode
+            .inclusive_siblings()
+            .filter(|dnode| with_defaults || !dnode.is_default())
+        {
+            cmd_show_config_hierarchical_node(
+                &mut output,
+                &dnode,
+                with_defaults,
+                colors,
+                0,
+            );
+        }
+    }
+
+    output
+}
+
+fn cmd_show_config_hierarchical_node(
+    output: &mut String,
+    dnode: &DataNodeRef<'_>,
+    with_defaults: bool,
+    colors: bool,
+    level: usize,
+) {
+    let snode = dnode.schema();
+    let indent = "    ".repeat(level);
+
+    // Build statement.
+    let keyword = if snode.kind() == SchemaNodeKind::List {
+        OutputStyle::ListHeader
+    } else {
+        OutputStyle::Keyword
+    };
+    let mut tokens = vec![keyword.paint(colors, snode.name())];
+    for dnode in dnode.list_keys() {
+        let value = dnode.value_canonical().unwrap();
+        tokens.push(value_token(&value, colors));
+    }
+    if let Some(value) =
+        dnode.value_canonical().filter(|value| !value.is_empty())
+    {
+        tokens.push(value_token(&value, colors));
+    }
+
+    // Print statement, followed by its children if any.
+    let children = dnode
+        .children()
+        .filter(|dnode| !dnode.schema().is_list_key())
+        .filter(|dnode| with_defaults || !dnode.is_default())
+        .collect::<Vec<_>>();
+    match snode.kind() {
+        SchemaNodeKind::Container | SchemaNodeKind::List
+            if !children.is_empty() =>
+        {
+            writeln!(output, "{}{} {{", indent, tokens.join(" ")).unwrap();
+            for dnode in &children {
+                cmd_show_config_hierarchical_node(
+                    output,
+                    dnode,
+                    with_defaults,
+                    colors,
+                    level + 1,
+                );
+            }
+            writeln!(output, "{}}}", indent).unwrap();
+        }
+        SchemaNodeKind::Container if snode.is_np_container() => (),
+        _ => {
+            writeln!(output, "{}{};", indent, tokens.join(" ")).unwrap();
+        }
+    }
+}
+
+fn cmd_show_config_set(
+    config: &DataTree<'static>,
+    with_defaults: bool,
+    colors: bool,
+) -> String {
+    let mut output = String::new();
+
+    // Iterate over data nodes that represent full commands, skipping list
+    // entries and presence containers that have child commands.
+    for dnode in config
+        .traverse()
+        .filter(is_full_command)
+        .filter(|dnode| with_defaults || !dnode.is_default())
+        .filter(|dnode| {
+            !dnode
+                .children()
+                .filter(|dnode| !dnode.schema().is_list_key())
+                .any(|dnode| with_defaults || !dnode.is_default())
+        })
+    {
+        let tokens = full_command_tokens(&dnode, colors);
+        writeln!(output, "set {}", tokens.join(" ")).unwrap();
+    }
+
+    output
+}
+
+// Colorizes the lines of a diff according to their prefixes.
+fn colorize_diff(diff: &str, colors: bool) -> String {
+    if !colors {
+        return diff.to_owned();
+    }
+
+    let mut output = String::new();
+    for line in diff.lines() {
+        let style = if line.starts_with("+++") || line.starts_with("---") {
+            OutputStyle::DiffHeader
+        } else if line.starts_with("@@") {
+            OutputStyle::DiffHunk
+        } else if line.starts_with('+') {
+            OutputStyle::DiffAdd
+        } else if line.starts_with('-') {
+            OutputStyle::DiffRemove
+        } else {
+            OutputStyle::Keyword
+        };
+        writeln!(output, "{}", style.paint(colors, line)).unwrap();
+    }
+    output
+}
+
+// Checks whether the given data node represents a full CLI command.
+fn is_full_command(dnode: &DataNodeRef<'_>) -> bool {
+    let snode = dnode.schema();
+    match snode.kind() {
+        SchemaNodeKind::Container => !snode.is_np_container(),
+        SchemaNodeKind::Leaf => !snode.is_list_key(),
+        SchemaNodeKind::LeafList => true,
+        SchemaNodeKind::List => true,
+        _ => false,
+    }
+}
+
+// Returns the tokens of the CLI command corresponding to the given data node,
+// starting from the configuration root.
+fn full_command_tokens(dnode: &DataNodeRef<'_>, colors: bool) -> Vec<String> {
+    let mut tokens = vec![];
+    for dnode in dnode
+        .inclusive_ancestors()
+        .collect::<Vec<DataNodeRef<'_>>>()
+        .iter()
+        .rev()
+    {
+        tokens.push(OutputStyle::Keyword.paint(colors, dnode.schema().name()));
+        for dnode in dnode.list_keys() {
+            let value = dnode.value_canonical().unwrap();
+            tokens.push(value_token(&value, colors));
+        }
+        if let Some(value) =
+            dnode.value_canonical().filter(|value| !value.is_empty())
+        {
+            tokens.push(value_token(&value, colors));
+        }
+    }
+    tokens
+}
+
+// Formats a leaf or list key value as a command token, quoted if necessary
+// so that it's read back unchanged.
+fn value_token(value: &str, colors: bool) -> String {
+    OutputStyle::Value.paint(colors, &parser::quote_word(value))
+}
+
 fn cmd_show_config_yang(
     config: &DataTree<'static>,
     format: DataFormat,
@@ -601,26 +1165,100 @@ fn cmd_show_config_yang(
     Ok(data)
 }
 
+// Converts a (possibly partial) configuration command into the XPath of the
+// corresponding configuration subtree.
+fn config_path_to_xpath(
+    commands: &Commands,
+    path: &str,
+) -> Result<String, String> {
+    let (token_id, mut args) = parser::parse_config_path(commands, path)
+        .map_err(|error| format!("invalid configuration path: {}", error))?;
+
+    let mut xpath = String::new();
+    let token_ids = token_id
+        .ancestors(&commands.arena)
+        .take_while(|token_id| *token_id != commands.config_root_yang)
+        .collect::<Vec<_>>();
+    for token_id in token_ids.into_iter().rev() {
+        let token = commands.get_token(token_id);
+        let snode = token.snode.as_ref().unwrap();
+        if token.kind == TokenKind::Word {
+            write!(xpath, "/{}:{}", snode.module().name(), snode.name())
+                .unwrap();
+        } else {
+            let (_, value) = args.pop_front().unwrap();
+            let name = if snode.is_list_key() {
+                snode.name()
+            } else {
+                "."
+            };
+            xpath += &path_predicate(name, &value);
+        }
+    }
+
+    Ok(xpath)
+}
+
+// Returns a copy of the configuration subtrees matching the given XPath,
+// including all their parent nodes.
+fn config_subtree(
+    config: &DataTree<'static>,
+    xpath: &str,
+) -> Result<DataTree<'static>, String> {
+    let yang_ctx = YANG_CTX.get().unwrap();
+    let mut subtree = DataTree::new(yang_ctx);
+    for dnode in config
+        .find_xpath(xpath)
+        .map_err(|error| format!("failed to find configuration: {}", error))?
+    {
+        let dup = dnode.duplicate(true).map_err(|error| {
+            format!("failed to copy configuration: {}", error)
+        })?;
+        subtree.merge(&dup).map_err(|error| {
+            format!("failed to copy configuration: {}", error)
+        })?;
+    }
+
+    Ok(subtree)
+}
+
 pub(crate) fn cmd_show_config(
-    _commands: &Commands,
+    commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     // Parse parameters.
-    let config_type = get_arg(&mut args, "configuration");
-    let config_type = match config_type.as_str() {
-        "candidate" => ConfigurationType::Candidate,
-        "running" => ConfigurationType::Running,
-        _ => panic!("unexpected argument"),
+    let config_type = get_opt_arg(&mut args, "configuration");
+    let xpath = match get_opt_arg(&mut args, "path") {
+        Some(path) => Some(config_path_to_xpath(commands, &path)?),
+        // A plain "show" displays the candidate configuration of the current
+        // CLI node.
+        None if config_type.is_none() => session.mode().data_path(),
+        None => None,
+    };
+    let config_type = match config_type.as_deref() {
+        Some("candidate") | None => ConfigurationType::Candidate,
+        Some("running") => ConfigurationType::Running,
+        Some(_) => panic!("unexpected argument"),
     };
     let with_defaults = get_opt_arg(&mut args, "with-defaults").is_some();
     let format = get_opt_arg(&mut args, "format");
+    let colors = use_colors(session);
 
     // Get configuration.
-    let config = session.get_configuration(config_type);
+    let subtree;
+    let mut config = session.get_configuration(config_type);
+    if let Some(xpath) = xpath {
+        subtree = config_subtree(config, &xpath)?;
+        config = &subtree;
+    }
 
     // Display configuration.
     let data = match format.as_deref() {
+        Some("hierarchical") => {
+            cmd_show_config_hierarchical(config, with_defaults, colors)
+        }
+        Some("set") => cmd_show_config_set(config, with_defaults, colors),
         Some("json") => {
             cmd_show_config_yang(config, DataFormat::JSON, with_defaults)?
         }
@@ -628,7 +1266,7 @@ pub(crate) fn cmd_show_config(
             cmd_show_config_yang(config, DataFormat::XML, with_defaults)?
         }
         Some(_) => panic!("unknown format"),
-        None => cmd_show_config_cmds(config, with_defaults),
+        None => cmd_show_config_cmds(config, with_defaults, colors),
     };
     if let Err(error) = page_output(session, &data) {
         println!("% failed to print configuration: {}", error)
@@ -637,23 +1275,321 @@ pub(crate) fn cmd_show_config(
     Ok(false)
 }
 
+fn cmd_show_config_changes_cmds(
+    old: &DataTree<'static>,
+    new: &DataTree<'static>,
+) -> Result<String, String> {
+    let mut output = String::new();
+
+    let diff = old.diff(new, DataDiffFlags::DEFAULTS).map_err(|error| {
+        format!("failed to compare configurations: {}", error)
+    })?;
+    for (op, dnode) in diff.iter() {
+        match op {
+            DataDiffOp::Create | DataDiffOp::Delete => {
+                let sign = if op == DataDiffOp::Create { '+' } else { '-' };
+                for dnode in dnode
+                    .traverse()
+                    .filter(is_full_command)
+                    .filter(|dnode| !dnode.is_default())
+                {
+                    let tokens = full_command_tokens(&dnode, false);
+                    writeln!(output, "{} {}", sign, tokens.join(" ")).unwrap();
+                }
+            }
+            DataDiffOp::Replace => {
+                let tokens = full_command_tokens(&dnode, false);
+                if let Some(meta) =
+                    dnode.meta().find(|meta| meta.name() == "orig-value")
+                {
+                    let mut orig_tokens = tokens.clone();
+                    if let Some(value) = orig_tokens.last_mut() {
+                        *value = meta.value().to_owned();
+                    }
+                    writeln!(output, "- {}", orig_tokens.join(" ")).unwrap();
+                }
+                writeln!(output, "+ {}", tokens.join(" ")).unwrap();
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+fn cmd_show_config_changes_yang(
+    old: &DataTree<'static>,
+    new: &DataTree<'static>,
+    format: DataFormat,
+) -> Result<String, String> {
+    let diff = old.diff(new, DataDiffFlags::DEFAULTS).map_err(|error| {
+        format!("failed to compare configurations: {}", error)
+    })?;
+    let data = diff
+        .print_string(format, DataPrinterFlags::WITH_SIBLINGS)
+        .map_err(|error| format!("failed to print changes: {}", error))?;
+    Ok(data)
+}
+
 pub(crate) fn cmd_show_config_changes(
+    _commands: &Commands,
+    session: &mut Session,
+    mut args: ParsedArgs,
+) -> Result<bool, Error> {
+    // Parse parameters.
+    let config_type = get_opt_arg(&mut args, "configuration");
+    let config_type = match config_type.as_deref() {
+        Some("candidate") | None => ConfigurationType::Candidate,
+        Some("running") => ConfigurationType::Running,
+        Some(_) => panic!("unexpected argument"),
+    };
+    let transaction_id = get_opt_arg(&mut args, "transaction-id")
+        .map(|id| {
+            id.parse::<u32>()
+                .map_err(|_| format!("invalid transaction ID: {}", id))
+        })
+        .transpose()?;
+    let format = get_opt_arg(&mut args, "format");
+
+    // Get configurations to compare.
+    let transaction = match transaction_id {
+        Some(transaction_id) => {
+            Some((transaction_id, fetch_transaction(session, transaction_id)?))
+        }
+        None => None,
+    };
+    let (old, old_title) = match &transaction {
+        Some((transaction_id, config)) => {
+            (config, format!("transaction {}", transaction_id))
+        }
+        None => (
+            session.get_configuration(ConfigurationType::Running),
+            "running configuration".to_owned(),
+        ),
+    };
+    let new = session.get_configuration(config_type);
+    let new_title = match config_type {
+        ConfigurationType::Running => "running configuration",
+        ConfigurationType::Candidate => "candidate configuration",
+    };
+
+    // Display configuration changes.
+    let data = match format.as_deref() {
+        Some("commands") => cmd_show_config_changes_cmds(old, new)?,
+        Some("json") => {
+            cmd_show_config_changes_yang(old, new, DataFormat::JSON)?
+        }
+        Some("xml") => cmd_show_config_changes_yang(old, new, DataFormat::XML)?,
+        Some(_) => panic!("unknown format"),
+        None => {
+            let old = cmd_show_config_cmds(old, false, false);
+            let new = cmd_show_config_cmds(new, false, false);
+            let diff = TextDiff::from_lines(&old, &new);
+            diff.unified_diff()
+                .context_radius(9)
+                .header(&old_title, new_title)
+                .to_string()
+        }
+    };
+
+    // Colorize additions and removals.
+    let data = match format.as_deref() {
+        Some("commands") | None => colorize_diff(&data, use_colors(session)),
+        _ => data,
+    };
+    if let Err(error) = page_output(session, data.trim_end_matches('\n')) {
+        println!("% failed to print configuration changes: {}", error)
+    }
+
+    Ok(false)
+}
+
+// ===== "save <candidate|running>" =====
+
+pub(crate) fn cmd_save_config(
+    _commands: &Commands,
+    session: &mut Session,
+    mut args: ParsedArgs,
+) -> Result<bool, Error> {
+    // Parse parameters.
+    let config_type = get_arg(&mut args, "configuration");
+    let config_type = match config_type.as_str() {
+        "candidate" => ConfigurationType::Candidate,
+        "running" => ConfigurationType::Running,
+        _ => panic!("unexpected argument"),
+    };
+    let path = get_arg(&mut args, "file");
+    let format = get_opt_arg(&mut args, "format");
+
+    // Build header.
+    let header = [
+        format!("Hostname: {}", session.hostname()),
+        format!(
+            "Holo daemon version: {}",
+            session.version().unwrap_or_else(|_| "unknown".to_owned())
+        ),
+        format!("Saved on: {}", chrono::Local::now().to_rfc2822()),
+    ];
+
+    // Render configuration. JSON doesn't support comments, hence the header
+    // is omitted in that case.
+    let config = session.get_configuration(config_type);
+    let data = match format.as_deref() {
+        Some("cli") | None => {
+            let mut data = String::new();
+            for line in &header {
+                writeln!(data, "! {}", line).unwrap();
+            }
+            data + &cmd_show_config_cmds(config, false, false)
+        }
+        Some("hierarchical") => {
+            let mut data = String::new();
+            for line in &header {
+                writeln!(data, "# {}", line).unwrap();
+            }
+            data + &cmd_show_config_hierarchical(config, false, false)
+        }
+        Some("set") => {
+            let mut data = String::new();
+            for line in &header {
+                writeln!(data, "# {}", line).unwrap();
+            }
+            data + &cmd_show_config_set(config, false, false)
+        }
+        Some("json") => cmd_show_config_yang(config, DataFormat::JSON, false)?,
+        Some("xml") => {
+            let mut data = String::new();
+            writeln!(data, "<!--").unwrap();
+            for line in &header {
+                writeln!(data, "  {}", line).unwrap();
+            }
+            writeln!(data, "-->").unwrap();
+            data + &cmd_show_config_yang(config, DataFormat::XML, false)?
+        }
+        Some(_) => panic!("unknown format"),
+    };
+
+    // Write configuration to the file.
+    write_file_atomic(&path, &data).map_err(|error| {
+        format!("failed to save configuration to {}: {}", path, error)
+    })?;
+    println!("% configuration saved to {}", path);
+
+    Ok(false)
+}
+
+// ===== "show history" =====
+
+pub(crate) fn cmd_show_history(
+    _commands: &Commands,
+    session: &mut Session,
+    mut args: ParsedArgs,
+) -> Result<bool, Error> {
+    // Search the histories of both CLI modes.
+    if let Some(text) = get_opt_arg(&mut args, "text") {
+        for (configure, mode) in [(false, "exec"), (true, "config")] {
+            for entry in read_history(session, configure)
+                .iter()
+                .filter(|entry| entry.contains(&text))
+            {
+                println!("{:>6}  {}", mode, entry);
+            }
+        }
+        return Ok(false);
+    }
+
+    let count = match get_opt_arg(&mut args, "count") {
+        Some(count) => count
+            .parse::<usize>()
+            .map_err(|_| format!("invalid number of entries: {}", count))?,
+        None => usize::MAX,
+    };
+    let entries = read_history(session, session.mode().is_configure());
+    let skip = entries.len().saturating_sub(count);
+    for (index, entry) in entries.iter().enumerate().skip(skip) {
+        println!("{:>5}  {}", index + 1, entry);
+    }
+
+    Ok(false)
+}
+
+// ===== "show history edits" =====
+
+pub(crate) fn cmd_show_history_edits(
     _commands: &Commands,
     session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
-    let running = session.get_configuration(ConfigurationType::Running);
-    let running = cmd_show_config_cmds(running, false);
-    let candidate = session.get_configuration(ConfigurationType::Candidate);
-    let candidate = cmd_show_config_cmds(candidate, false);
-
-    let diff = TextDiff::from_lines(&running, &candidate);
-    print!(
-        "{}",
-        diff.unified_diff()
-            .context_radius(9)
-            .header("running configuration", "candidate configuration")
-    );
+) -> Result<bool, Error> {
+    for (index, edit) in session.candidate_edits().enumerate() {
+        if edit.cli_path.is_empty() {
+            println!("{:>4}  {}", index + 1, edit.command);
+        } else {
+            println!("{:>4}  ({}) {}", index + 1, edit.cli_path, edit.command);
+        }
+    }
+
+    Ok(false)
+}
+
+// ===== "show checkpoints" =====
+
+pub(crate) fn cmd_show_checkpoints(
+    _commands: &Commands,
+    session: &mut Session,
+    _args: ParsedArgs,
+) -> Result<bool, Error> {
+    for name in session.candidate_checkpoints() {
+        println!("{}", name);
+    }
+
+    Ok(false)
+}
+
+// ===== "show cli settings" =====
+
+pub(crate) fn cmd_show_cli_settings(
+    _commands: &Commands,
+    session: &mut Session,
+    _args: ParsedArgs,
+) -> Result<bool, Error> {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    for (option, value) in session.settings().options() {
+        table.add_row(row![option, value]);
+    }
+
+    let titles = row!["Option", "Value"];
+    if let Err(error) = page_table(session, &mut table, titles) {
+        println!("% failed to display data: {}", error)
+    }
+
+    Ok(false)
+}
+
+// ===== "show template" =====
+
+pub(crate) fn cmd_show_template(
+    _commands: &Commands,
+    session: &mut Session,
+    mut args: ParsedArgs,
+) -> Result<bool, Error> {
+    let Some(name) = get_opt_arg(&mut args, "name") else {
+        // List available templates.
+        for name in template::list() {
+            println!("{}", name);
+        }
+        return Ok(false);
+    };
+
+    let mut data = template::read(&name)?;
+    if get_opt_arg(&mut args, "expanded").is_some() {
+        let vars = get_opt_arg(&mut args, "variables").unwrap_or_default();
+        let vars = template::parse_vars(&vars)?;
+        data = template::expand(&data, &vars)?;
+    }
+
+    if let Err(error) = page_output(session, &data) {
+        println!("% failed to print template: {}", error)
+    }
 
     Ok(false)
 }
@@ -664,7 +1600,7 @@ pub(crate) fn cmd_show_state(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let xpath = get_opt_arg(&mut args, "xpath");
     let format = get_opt_arg(&mut args, "format");
     let format = match format.as_deref() {
@@ -693,7 +1629,7 @@ pub(crate) fn cmd_show_yang_modules(
     _commands: &Commands,
     _session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     // Create the table
     let mut table = Table::new();
     table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
@@ -739,7 +1675,7 @@ pub(crate) fn cmd_show_isis_interface(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     YangTableBuilder::new(session, DataType::All)
         .xpath(XPATH_PROTOCOL)
         .filter_list_key("type", Some(PROTOCOL_ISIS))
@@ -759,7 +1695,7 @@ pub(crate) fn cmd_show_isis_adjacency(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let hostnames = isis_hostnames(session)?;
     YangTableBuilder::new(session, DataType::State)
         .xpath(XPATH_PROTOCOL)
@@ -789,7 +1725,7 @@ pub(crate) fn cmd_show_isis_database(
     _commands: &Commands,
     session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let hostnames = isis_hostnames(session)?;
     YangTableBuilder::new(session, DataType::State)
         .xpath(XPATH_PROTOCOL)
@@ -821,7 +1757,7 @@ pub(crate) fn cmd_show_isis_route(
     _commands: &Commands,
     session: &mut Session,
     _args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     YangTableBuilder::new(session, DataType::State)
         .xpath(XPATH_PROTOCOL)
         .filter_list_key("type", Some(PROTOCOL_ISIS))
@@ -888,7 +1824,7 @@ pub(crate) fn cmd_show_ospf_interface(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let protocol = match get_arg(&mut args, "protocol").as_str() {
         "ospfv2" => PROTOCOL_OSPFV2,
         "ospfv3" => PROTOCOL_OSPFV3,
@@ -927,7 +1863,7 @@ pub(crate) fn cmd_show_ospf_interface_detail(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let mut output = String::new();
 
     // Parse arguments.
@@ -999,7 +1935,7 @@ pub(crate) fn cmd_show_ospf_neighbor(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let protocol = match get_arg(&mut args, "protocol").as_str() {
         "ospfv2" => PROTOCOL_OSPFV2,
         "ospfv3" => PROTOCOL_OSPFV3,
@@ -1045,7 +1981,7 @@ pub(crate) fn cmd_show_ospf_neighbor_detail(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let mut output = String::new();
 
     // Parse arguments.
@@ -1137,7 +2073,7 @@ pub(crate) fn cmd_show_ospf_database_as(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let protocol = match get_arg(&mut args, "protocol").as_str() {
         "ospfv2" => PROTOCOL_OSPFV2,
         "ospfv3" => PROTOCOL_OSPFV3,
@@ -1180,7 +2116,7 @@ pub(crate) fn cmd_show_ospf_database_area(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let protocol = match get_arg(&mut args, "protocol").as_str() {
         "ospfv2" => PROTOCOL_OSPFV2,
         "ospfv3" => PROTOCOL_OSPFV3,
@@ -1225,7 +2161,7 @@ pub(crate) fn cmd_show_ospf_database_link(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let protocol = match get_arg(&mut args, "protocol").as_str() {
         "ospfv2" => PROTOCOL_OSPFV2,
         "ospfv3" => PROTOCOL_OSPFV3,
@@ -1272,7 +2208,7 @@ pub(crate) fn cmd_show_ospf_route(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let protocol = match get_arg(&mut args, "protocol").as_str() {
         "ospfv2" => PROTOCOL_OSPFV2,
         "ospfv3" => PROTOCOL_OSPFV3,
@@ -1300,7 +2236,7 @@ pub(crate) fn cmd_show_ospf_hostnames(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let protocol = match get_arg(&mut args, "protocol").as_str() {
         "ospfv2" => PROTOCOL_OSPFV2,
         "ospfv3" => PROTOCOL_OSPFV3,
@@ -1360,7 +2296,7 @@ pub(crate) fn cmd_show_rip_interface(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     // Parse arguments.
     let protocol = match get_arg(&mut args, "protocol").as_str() {
         "ripv2" => PROTOCOL_RIPV2,
@@ -1384,7 +2320,7 @@ pub(crate) fn cmd_show_rip_interface_detail(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let mut output = String::new();
 
     // Parse arguments.
@@ -1455,7 +2391,7 @@ pub(crate) fn cmd_show_rip_neighbor(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     // Parse arguments.
     let (protocol, afi, address) = match get_arg(&mut args, "protocol").as_str()
     {
@@ -1483,7 +2419,7 @@ pub(crate) fn cmd_show_rip_neighbor_detail(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let mut output = String::new();
 
     // Parse arguments.
@@ -1545,7 +2481,7 @@ pub(crate) fn cmd_show_rip_route(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     // Parse arguments.
     let (protocol, afi, prefix) = match get_arg(&mut args, "protocol").as_str()
     {
@@ -1593,7 +2529,7 @@ pub(crate) fn cmd_show_mpls_ldp_discovery(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     YangTableBuilder::new(session, DataType::State)
         .xpath(XPATH_PROTOCOL)
         .filter_list_key("type", Some(PROTOCOL_MPLS_LDP))
@@ -1614,7 +2550,7 @@ pub(crate) fn cmd_show_mpls_ldp_discovery_detail(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let mut output = String::new();
 
     // Parse arguments.
@@ -1706,7 +2642,7 @@ pub(crate) fn cmd_show_mpls_ldp_peer(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     YangTableBuilder::new(session, DataType::State)
         .xpath(XPATH_PROTOCOL)
         .filter_list_key("type", Some(PROTOCOL_MPLS_LDP))
@@ -1728,7 +2664,7 @@ pub(crate) fn cmd_show_mpls_ldp_peer_detail(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     let mut output = String::new();
 
     // Parse arguments.
@@ -1881,7 +2817,7 @@ pub(crate) fn cmd_show_mpls_ldp_binding_address(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     YangTableBuilder::new(session, DataType::State)
         .xpath(XPATH_PROTOCOL)
         .filter_list_key("type", Some(PROTOCOL_MPLS_LDP))
@@ -1915,7 +2851,7 @@ pub(crate) fn cmd_show_mpls_ldp_binding_fec(
     _commands: &Commands,
     session: &mut Session,
     mut args: ParsedArgs,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     YangTableBuilder::new(session, DataType::State)
         .xpath(XPATH_PROTOCOL)
         .filter_list_key("type", Some(PROTOCOL_MPLS_LDP))
@@ -1954,3 +2890,87 @@ pub(crate) fn cmd_show_mpls_ldp_binding_fec(
 
     Ok(false)
 }
+
+// ===== unit tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_text_words() {
+        assert_eq!(wrap_text("one two three", 7), "one two\nthree");
+        assert_eq!(wrap_text("one two three", 13), "one two three");
+        assert_eq!(wrap_text("one\ntwo three", 5), "one\ntwo\nthree");
+    }
+
+    #[test]
+    fn wrap_text_long_word() {
+        assert_eq!(wrap_text("abcdefghij", 4), "abcd\nefgh\nij");
+        assert_eq!(wrap_text("ab abcdefgh", 4), "ab\nabcd\nefgh");
+    }
+
+    #[test]
+    fn fit_table_wraps_widest_column() {
+        let mut table = Table::new();
+        table.add_row(row!["eth0", "a b c d e f g h i j k l m n o p"]);
+        let mut titles = row!["Name", "Description"];
+        fit_table(&mut table, &mut titles, 30);
+
+        let row = table.get_row(0).unwrap();
+        assert_eq!(row.get_cell(0).unwrap().get_content(), "eth0");
+        let description = row.get_cell(1).unwrap().get_content();
+        assert!(description.lines().count() > 1);
+        assert!(description.lines().all(|line| line.len() <= 30 - 4 - 5));
+    }
+
+    #[test]
+    fn fit_table_counts_title_columns() {
+        let mut table = Table::new();
+        table.add_row(row!["abcdefghijklmnop"]);
+        let mut titles = row!["Name", "abcdefghijklmnopqrstuvwxyz"];
+        fit_table(&mut table, &mut titles, 30);
+
+        // The title-only column takes part in the width computation.
+        let row = table.get_row(0).unwrap();
+        assert_eq!(row.len(), 1);
+        assert!(row.get_cell(0).unwrap().get_content().contains('\n'));
+        assert!(titles.get_cell(1).unwrap().get_content().contains('\n'));
+    }
+
+    #[test]
+    fn fit_table_keeps_fitting_table() {
+        let mut table = Table::new();
+        table.add_row(row!["eth0", "up"]);
+        let mut titles = row!["Name", "Status"];
+        fit_table(&mut table, &mut titles, 80);
+
+        let row = table.get_row(0).unwrap();
+        assert_eq!(row.get_cell(1).unwrap().get_content(), "up");
+        assert_eq!(titles.get_cell(1).unwrap().get_content(), "Status");
+    }
+
+    #[test]
+    fn set_format_round_trip() {
+        let description = "uplink to r2 \"core\" ! #1";
+        let data = format!(
+            "set interfaces interface eth0 description {}\n",
+            value_token(description, false)
+        );
+        let format = config_file::ConfigFileFormat::detect(&data);
+        assert_eq!(format, config_file::ConfigFileFormat::Set);
+
+        let cmds = config_file::parse(&data, format).unwrap();
+        let line = parser::normalize_input_line(&cmds[0].1).unwrap();
+        assert_eq!(
+            parser::split_words(&line),
+            [
+                "interfaces",
+                "interface",
+                "eth0",
+                "description",
+                description
+            ]
+        );
+    }
+}
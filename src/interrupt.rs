@@ -0,0 +1,48 @@
+//
+// Copyright (c) The Holo Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Whether Ctrl-C interrupts the command in progress instead of terminating
+// the CLI.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Whether Ctrl-C was pressed since the current command started.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+// ===== global functions =====
+
+// Installs the Ctrl-C handler. Used only in interactive sessions, where the
+// user should be able to interrupt long-running commands and get back to the
+// prompt.
+pub(crate) fn init() {
+    ENABLED.store(true, Ordering::Relaxed);
+    std::thread::spawn(|| {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to obtain a new runtime object");
+        runtime.block_on(async {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                INTERRUPTED.store(true, Ordering::Relaxed);
+            }
+        });
+    });
+}
+
+pub(crate) fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// Clears the interruption flag before a new command is executed.
+pub(crate) fn reset() {
+    INTERRUPTED.store(false, Ordering::Relaxed);
+}
+
+// Checks whether the command in progress was interrupted.
+pub(crate) fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
@@ -7,15 +7,25 @@
 #![feature(let_chains)]
 
 mod client;
+mod config_file;
 mod error;
 mod internal_commands;
+mod interrupt;
+mod pager;
 mod parser;
 mod session;
+mod template;
 mod terminal;
+#[cfg(test)]
+mod test_util;
 mod token;
+mod token_user;
 mod token_xml;
 mod token_yang;
+mod user_config;
 
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, OnceLock};
 
 use clap::{App, Arg};
@@ -23,11 +33,13 @@ use reedline::Signal;
 use yang3::context::{Context, ContextFlags};
 
 use crate::client::grpc::GrpcClient;
-use crate::client::Client;
+use crate::client::{Client, ClientOptions};
 use crate::error::Error;
-use crate::session::{CommandMode, Session};
+use crate::parser::ParsedArgs;
+use crate::session::{CommandMode, ReconnectStatus, Session};
 use crate::terminal::CliPrompt;
 use crate::token::{Action, Commands};
+use crate::user_config::{Settings, UserConfig};
 
 // Global YANG context.
 pub static YANG_CTX: OnceLock<Arc<Context>> = OnceLock::new();
@@ -35,6 +47,14 @@ pub static YANG_CTX: OnceLock<Arc<Context>> = OnceLock::new();
 // Default YANG modules cache directory.
 pub const YANG_MODULES_DIR: &str = "/usr/local/share/holo-cli/modules";
 
+// Process exit status codes.
+const EXIT_SUCCESS: i32 = 0;
+const EXIT_FAILURE: i32 = 1;
+
+// Environment variable passing the autosaved candidate configuration to the
+// new process when the CLI restarts.
+const RESTART_AUTOSAVE_ENV: &str = "HOLO_CLI_AUTOSAVE";
+
 pub struct Cli {
     commands: Commands,
     session: Session,
@@ -43,57 +63,142 @@ pub struct Cli {
 // ===== impl Cli =====
 
 impl Cli {
-    fn new(use_pager: bool, client: Box<dyn Client>) -> Cli {
+    fn new(
+        settings: Settings,
+        client: Box<dyn Client>,
+        user_config: &UserConfig,
+    ) -> Cli {
         // Generate commands.
         let mut commands = Commands::new();
-        commands.gen_cmds();
+        commands.gen_cmds(user_config);
 
         // Create CLI session.
-        let session = Session::new(use_pager, client);
+        let session = Session::new(settings, client);
 
         Cli { commands, session }
     }
 
     fn enter_command(&mut self, line: &str) -> Result<bool, Error> {
-        // Normalize input line.
-        let line = match parser::normalize_input_line(line) {
-            Some(line) => line,
-            None => return Ok(false),
-        };
+        enter_command(&self.commands, &mut self.session, line)
+    }
+}
+
+// ===== global functions =====
 
-        // Parse command.
-        let pcmd =
-            parser::parse_command(&mut self.session, &self.commands, &line)
-                .map_err(Error::Parser)?;
-        let token = self.commands.get_token(pcmd.token_id);
-        let negate = pcmd.negate;
-        let args = pcmd.args;
-
-        // Process command.
-        let mut exit = false;
-        if let Some(action) = &token.action {
-            match action {
-                Action::ConfigEdit(snode) => {
-                    // Edit configuration & update CLI node if necessary.
-                    self.session
-                        .edit_candidate(negate, snode, args)
-                        .map_err(Error::EditConfig)?;
+pub(crate) fn enter_command(
+    commands: &Commands,
+    session: &mut Session,
+    line: &str,
+) -> Result<bool, Error> {
+    enter_command_nested(commands, session, line, true)
+}
+
+fn enter_command_nested(
+    commands: &Commands,
+    session: &mut Session,
+    line: &str,
+    allow_macros: bool,
+) -> Result<bool, Error> {
+    // Normalize input line.
+    let line = match parser::normalize_input_line(line) {
+        Some(line) => line,
+        None => return Ok(false),
+    };
+
+    // Parse command.
+    let pcmd = parser::parse_command(session, commands, &line)
+        .map_err(Error::Parser)?;
+    let token = commands.get_token(pcmd.token_id);
+    let negate = pcmd.negate;
+    let mut args = pcmd.args;
+
+    // Process command.
+    let mut exit = false;
+    if let Some(action) = &token.action {
+        match action {
+            Action::ConfigEdit(snode) => {
+                // Edit configuration & update CLI node if necessary.
+                session
+                    .edit_candidate(&line, negate, snode, args)
+                    .map_err(Error::EditConfig)?;
+            }
+            Action::Callback(callback) => {
+                // Execute callback.
+                exit = (callback)(commands, session, args)?;
+            }
+            Action::Macro(lines) => {
+                // Macros can't call other macros, preventing infinite loops.
+                if !allow_macros {
+                    return Err(Error::Callback(
+                        "macros can't be nested".to_owned(),
+                    ));
                 }
-                Action::Callback(callback) => {
-                    // Execute callback.
-                    exit = (callback)(&self.commands, &mut self.session, args)
-                        .map_err(Error::Callback)?;
+
+                // Execute commands, appending any extra arguments.
+                let extra = args.pop_front().map(|(_, value)| value);
+                for line in lines {
+                    let line = match &extra {
+                        Some(extra) => format!("{} {}", line, extra),
+                        None => line.clone(),
+                    };
+                    if enter_command_nested(commands, session, &line, false)? {
+                        exit = true;
+                        break;
+                    }
                 }
             }
         }
-
-        Ok(exit)
     }
+
+    Ok(exit)
 }
 
-// ===== global functions =====
+// Returns the per-user directory where configuration files are kept, following
+// the XDG Base Directory specification.
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+// Returns the per-user directory where persistent state is kept, following the
+// XDG Base Directory specification.
+pub(crate) fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+fn xdg_dir(var: &str, default: &str) -> Option<PathBuf> {
+    let dir = match std::env::var_os(var) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(default),
+    };
+    Some(dir.join("holo-cli"))
+}
+
+// Restarts the CLI with the same arguments, so that the YANG modules are
+// loaded again from scratch. The new process takes over the autosaved
+// candidate configuration, if any.
+fn restart(autosave_path: Option<PathBuf>) -> ! {
+    use std::os::unix::process::CommandExt;
+
+    let error = std::env::current_exe()
+        .map(|path| {
+            let mut command = std::process::Command::new(path);
+            command.args(std::env::args_os().skip(1));
+            if let Some(autosave_path) = autosave_path {
+                command.env(RESTART_AUTOSAVE_ENV, autosave_path);
+            }
+            command.exec()
+        })
+        .unwrap_or_else(|error| error);
+    eprintln!("% failed to restart: {}", error);
+    std::process::exit(EXIT_FAILURE);
+}
 
-fn read_config_file(mut cli: Cli, path: &str) {
+fn read_config_file(
+    mut cli: Cli,
+    path: &str,
+    continue_on_error: bool,
+    dry_run: bool,
+) -> i32 {
     // Enter configuration mode.
     let mode = CommandMode::Configure { nodes: vec![] };
     cli.session.mode_set(mode);
@@ -103,21 +208,88 @@ fn read_config_file(mut cli: Cli, path: &str) {
         Ok(file) => file,
         Err(error) => {
             eprintln!("% failed to read file path: {}", error);
-            return;
+            return EXIT_FAILURE;
         }
     };
 
-    // Read configuration.
-    for line in file.lines() {
-        if let Err(error) = cli.enter_command(line) {
+    // Read configuration, recording all changes as a single edit.
+    cli.session.edit_group_begin(&format!("load {}", path));
+    let errors = config_file::load(&cli.commands, &mut cli.session, &file);
+    cli.session.edit_group_end(false);
+    for (line, error) in &errors {
+        eprintln!("{}:{}: {}", path, line, error);
+    }
+    let errors = errors.len();
+    if errors > 0 && !continue_on_error {
+        eprintln!("% {} error(s) found, configuration not committed", errors);
+        return EXIT_FAILURE;
+    }
+
+    if dry_run {
+        // Validate configuration and display the changes it would make.
+        if let Err(error) = cli.session.candidate_validate() {
+            eprintln!("% {}", error);
+            return EXIT_FAILURE;
+        }
+        if let Err(error) = internal_commands::cmd_show_config_changes(
+            &cli.commands,
+            &mut cli.session,
+            ParsedArgs::new(),
+        ) {
+            eprintln!("% {}", error);
+            return EXIT_FAILURE;
+        }
+    } else {
+        // Commit configuration.
+        let comment = Some(format!("Configuration read from {}", path));
+        if let Err(error) = cli.session.candidate_commit(comment, None) {
             eprintln!("% {}", error);
+            return EXIT_FAILURE;
+        }
+    }
+
+    if errors > 0 {
+        EXIT_FAILURE
+    } else {
+        EXIT_SUCCESS
+    }
+}
+
+fn run_script(mut cli: Cli, path: &str, continue_on_error: bool) -> i32 {
+    // Read script from the standard input or from the filesystem.
+    let (name, script) = if path == "-" {
+        ("stdin", std::io::read_to_string(std::io::stdin()))
+    } else {
+        (path, std::fs::read_to_string(path))
+    };
+    let script = match script {
+        Ok(script) => script,
+        Err(error) => {
+            eprintln!("% failed to read script: {}", error);
+            return EXIT_FAILURE;
+        }
+    };
+
+    // Execute commands one line at a time.
+    let mut errors = 0;
+    for (index, line) in script.lines().enumerate() {
+        match cli.enter_command(line) {
+            Ok(true) => break,
+            Ok(false) => (),
+            Err(error) => {
+                eprintln!("{}:{}: {}", name, index + 1, error);
+                errors += 1;
+                if !continue_on_error {
+                    break;
+                }
+            }
         }
     }
 
-    // Commit configuration.
-    let comment = Some(format!("Configuration read from {}", path));
-    if let Err(err) = cli.session.candidate_commit(comment) {
-        eprintln!("% {}", err);
+    if errors > 0 {
+        EXIT_FAILURE
+    } else {
+        EXIT_SUCCESS
     }
 }
 
@@ -131,6 +303,24 @@ fn main() {
                 .value_name("path")
                 .help("Read configuration file"),
         )
+        .arg(
+            Arg::with_name("script")
+                .long("script")
+                .value_name("path")
+                .conflicts_with("file")
+                .help("Execute commands from a script file (\"-\" for stdin)"),
+        )
+        .arg(
+            Arg::with_name("continue-on-error")
+                .long("continue-on-error")
+                .help("Keep processing the file or script when a line fails"),
+        )
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .requires("file")
+                .help("Validate the configuration file without committing it"),
+        )
         .arg(
             Arg::with_name("no-colors")
                 .long("no-colors")
@@ -157,20 +347,86 @@ fn main() {
                 .help("Holo daemon IPv4/6 address: http://IP:Port")
                 .multiple(false),
         )
+        .arg(
+            Arg::with_name("connect-timeout")
+                .long("connect-timeout")
+                .value_name("SECONDS")
+                .help("Maximum time to wait for the connection to holod"),
+        )
+        .arg(
+            Arg::with_name("request-timeout")
+                .long("request-timeout")
+                .value_name("SECONDS")
+                .help("Maximum time to wait for each request (0 to disable)"),
+        )
+        .arg(
+            Arg::with_name("commit-timeout")
+                .long("commit-timeout")
+                .value_name("SECONDS")
+                .help("Maximum time to wait for each commit (0 to disable)"),
+        )
+        .arg(
+            Arg::with_name("profile")
+                .short("p")
+                .long("profile")
+                .value_name("NAME")
+                .help("Settings profile from the user configuration file"),
+        )
         .get_matches();
 
+    // Read user configuration and settings. Command-line parameters take
+    // precedence over the settings file.
+    let user_config = UserConfig::load();
+    let mut settings = match user_config.settings(matches.value_of("profile")) {
+        Ok(settings) => settings,
+        Err(error) => {
+            eprintln!("% {}", error);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+    if let Some(address) = matches.value_of("address") {
+        settings.address = Some(address.to_owned());
+    }
+    for (name, option) in [
+        ("connect-timeout", &mut settings.connect_timeout),
+        ("request-timeout", &mut settings.request_timeout),
+        ("commit-timeout", &mut settings.commit_timeout),
+    ] {
+        if let Some(value) = matches.value_of(name) {
+            match value.parse() {
+                Ok(value) => *option = Some(value),
+                Err(_) => {
+                    eprintln!("% invalid {}: {}", name, value);
+                    std::process::exit(EXIT_FAILURE);
+                }
+            }
+        }
+    }
+    if settings.connect_timeout == Some(0) {
+        eprintln!("% invalid connect-timeout: 0");
+        std::process::exit(EXIT_FAILURE);
+    }
+    if matches.is_present("no-colors") {
+        settings.colors = Some(false);
+    }
+    if matches.is_present("no-pager") {
+        settings.pager = Some(false);
+    }
+
     // Connect to the daemon.
-    let addr = matches
-        .value_of("address")
-        .unwrap_or("http://[::1]:50051")
-        .to_string();
+    let addr = settings.address().to_owned();
     let grpc_addr: &'static str = Box::leak(addr.into_boxed_str());
-    let mut client = match GrpcClient::connect(grpc_addr) {
+    let options = ClientOptions {
+        connect_timeout: settings.connect_timeout(),
+        request_timeout: settings.request_timeout(),
+        commit_timeout: settings.commit_timeout(),
+    };
+    let mut client = match GrpcClient::connect(grpc_addr, options) {
         Ok(client) => client,
         Err(error) => {
             eprintln!("Connection to holod failed: {}\n", error);
             eprintln!("Please ensure that holod is currently running.");
-            std::process::exit(1);
+            std::process::exit(EXIT_FAILURE);
         }
     };
 
@@ -186,7 +442,7 @@ fn main() {
             "Failed to create YANG modules directory ({}): {}",
             YANG_MODULES_DIR, error
         );
-        std::process::exit(1);
+        std::process::exit(EXIT_FAILURE);
     }
 
     // Set YANG search directory.
@@ -196,15 +452,34 @@ fn main() {
     client.load_modules(grpc_addr, &mut yang_ctx);
     YANG_CTX.set(Arc::new(yang_ctx)).unwrap();
 
+    // Commands are read from the standard input when it isn't a terminal.
+    let script = match matches.value_of("script") {
+        Some(path) => Some(path),
+        None if matches.values_of("command").is_none()
+            && !std::io::stdin().is_terminal() =>
+        {
+            Some("-")
+        }
+        None => None,
+    };
+
+    // The pager is only used in interactive sessions.
+    if matches.values_of("command").is_some()
+        || script.is_some()
+        || matches.is_present("file")
+    {
+        settings.pager = Some(false);
+    }
+
     // Initialize CLI master structure.
-    let use_pager = matches.values_of("command").is_none()
-        && !matches.is_present("no-pager");
-    let mut cli = Cli::new(use_pager, Box::new(client));
+    let mut cli = Cli::new(settings, Box::new(client), &user_config);
 
     // Read configuration file.
     if let Some(path) = matches.value_of("file") {
-        read_config_file(cli, path);
-        return;
+        let continue_on_error = matches.is_present("continue-on-error");
+        let dry_run = matches.is_present("dry-run");
+        let status = read_config_file(cli, path, continue_on_error, dry_run);
+        std::process::exit(status);
     }
 
     // Fetch hostname from running configuration and update the prompt.
@@ -212,26 +487,60 @@ fn main() {
 
     // Process commands passed as arguments, if any.
     if let Some(commands) = matches.values_of("command") {
+        let mut status = EXIT_SUCCESS;
         for command in commands {
             if let Err(error) = cli.enter_command(command) {
-                println!("% {}", error)
+                println!("% {}", error);
+                status = EXIT_FAILURE;
             }
         }
-        return;
+        std::process::exit(status);
     }
 
+    // Process script, if any.
+    if let Some(path) = script {
+        let continue_on_error = matches.is_present("continue-on-error");
+        let status = run_script(cli, path, continue_on_error);
+        std::process::exit(status);
+    }
+
+    // Save the candidate configuration to disk so that uncommitted changes
+    // survive an abrupt termination.
+    cli.session.autosave_enable();
+
+    // Take over the uncommitted changes of the process that restarted this
+    // one.
+    if let Some(path) = std::env::var_os(RESTART_AUTOSAVE_ENV) {
+        std::env::remove_var(RESTART_AUTOSAVE_ENV);
+        cli.session.autosave_adopt(Path::new(&path));
+    }
+
+    // Let Ctrl-C interrupt long-running commands.
+    interrupt::init();
+
     // Initialize reedline.
-    let mut prompt = CliPrompt::new(cli.session.prompt());
+    let mut prompt = CliPrompt::new(&cli.session);
     let cli = Arc::new(Mutex::new(cli));
-    let use_ansi_coloring = !matches.is_present("no-colors");
-    let mut le = terminal::reedline_init(cli.clone(), use_ansi_coloring);
+    let mut le = terminal::reedline_init(cli.clone());
 
     // Main loop.
     while let Signal::Success(line) =
         le.read_line(&prompt).expect("Failed to read line")
     {
-        let mut cli = cli.lock().unwrap();
-        match cli.enter_command(&line) {
+        // Save the history right away, making it available to other sessions.
+        let _ = le.sync_history();
+
+        let mut cli_guard = cli.lock().unwrap();
+        let configure = cli_guard.session.mode().is_configure();
+
+        // Display help for the current input line.
+        if line == terminal::HELP_REQUEST {
+            terminal::print_help(&cli_guard, le.current_buffer_contents());
+            continue;
+        }
+
+        interrupt::reset();
+        match cli_guard.enter_command(&line) {
             Ok(exit) => {
                 if exit {
                     break;
@@ -242,8 +551,49 @@ fn main() {
             }
         };
 
+        // Resynchronize with the daemon if the connection was reestablished.
+        match cli_guard.session.reconnect_check() {
+            Ok(None) => (),
+            Ok(Some(ReconnectStatus::Resynchronized { rebased })) => {
+                println!("% reconnected to holod");
+                if !rebased {
+                    println!(
+                        "% failed to rebase uncommitted changes on the new running configuration"
+                    );
+                    println!(
+                        "% they're kept relative to the previous running configuration and can't be committed, use \"discard\" to drop them"
+                    );
+                }
+            }
+            Ok(Some(ReconnectStatus::ModulesChanged)) => {
+                println!(
+                    "% YANG modules supported by holod changed, restarting"
+                );
+                if cli_guard.session.candidate_dirty() {
+                    println!(
+                        "% uncommitted changes can be recovered with \"restore autosave\""
+                    );
+                }
+                le.sync_history().expect("Failed to update history file");
+                restart(cli_guard.session.autosave_path());
+            }
+            Err(error) => {
+                println!("% failed to resynchronize with holod: {}", error)
+            }
+        }
+
         // Update CLI prompt.
-        prompt.update(cli.session.prompt());
+        prompt.update(&cli_guard.session);
+
+        // Reinitialize reedline if the terminal settings have changed.
+        if cli_guard.session.terminal_update_take() {
+            drop(cli_guard);
+            le.sync_history().expect("Failed to update history file");
+            le = terminal::reedline_init(cli.clone());
+        } else if cli_guard.session.mode().is_configure() != configure {
+            // Switch to the history of the new CLI mode.
+            le = le.with_history(terminal::history_init(&cli_guard.session));
+        }
     }
 
     // Update history log.
@@ -0,0 +1,265 @@
+//
+// Copyright (c) The Holo Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+use std::io::{IsTerminal, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{cursor, queue};
+
+use crate::terminal::{display_width, terminal_size};
+
+// Built-in pager, displaying the text one screen at a time, in the style of
+// "more". The displayed lines are kept in the terminal scrollback.
+struct Pager<'a> {
+    lines: Vec<&'a str>,
+    // Index of the next line to be displayed.
+    position: usize,
+    // Last search pattern.
+    pattern: Option<String>,
+}
+
+// Action requested at the pager prompt.
+enum PagerAction {
+    NextPage,
+    NextLine,
+    Search(String),
+    SearchNext,
+    Quit,
+}
+
+// ===== impl Pager =====
+
+impl<'a> Pager<'a> {
+    fn new(data: &'a str) -> Self {
+        Self {
+            lines: data.lines().collect(),
+            position: 0,
+            pattern: None,
+        }
+    }
+
+    fn run(&mut self) -> std::io::Result<()> {
+        let mut message = None;
+
+        self.display(page_rows())?;
+        while self.position < self.lines.len() {
+            match self.prompt(message.take())? {
+                PagerAction::NextPage => self.display(page_rows())?,
+                PagerAction::NextLine => self.display(1)?,
+                PagerAction::Search(pattern) => {
+                    self.pattern = Some(pattern);
+                    message = self.search()?;
+                }
+                PagerAction::SearchNext => message = self.search()?,
+                PagerAction::Quit => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    // Displays lines starting at the current position, until the given number
+    // of terminal rows is filled.
+    fn display(&mut self, rows: usize) -> std::io::Result<()> {
+        let (columns, _) = terminal_size();
+        let mut stdout = std::io::stdout().lock();
+        let mut displayed = 0;
+        for line in &self.lines[self.position..] {
+            if displayed >= rows {
+                break;
+            }
+
+            // Highlight matches of the last search pattern.
+            match &self.pattern {
+                Some(pattern) => writeln!(
+                    stdout,
+                    "{}",
+                    line.replace(
+                        pattern.as_str(),
+                        &format!("\x1b[7m{}\x1b[27m", pattern)
+                    )
+                )?,
+                None => writeln!(stdout, "{}", line)?,
+            }
+            displayed += line_rows(line, columns);
+            self.position += 1;
+        }
+        stdout.flush()
+    }
+
+    // Skips to the next line matching the last search pattern. Returns an
+    // error message to be displayed at the prompt when there's no match.
+    fn search(&mut self) -> std::io::Result<Option<String>> {
+        let Some(pattern) = &self.pattern else {
+            return Ok(Some("No previous search pattern".to_owned()));
+        };
+        let Some(found) = self.lines[self.position..]
+            .iter()
+            .position(|line| line.contains(pattern.as_str()))
+        else {
+            return Ok(Some("Pattern not found".to_owned()));
+        };
+
+        if found > 0 {
+            self.position += found;
+            println!("...skipping");
+        }
+        self.display(page_rows().saturating_sub(1).max(1))?;
+        Ok(None)
+    }
+
+    // Displays the status line and waits for a command.
+    fn prompt(&self, message: Option<String>) -> std::io::Result<PagerAction> {
+        let status = message.unwrap_or_else(|| {
+            format!("--More-- ({}%)", self.position * 100 / self.lines.len())
+        });
+
+        print_status(&status)?;
+        let action = loop {
+            let key = read_key()?;
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Char('c') if ctrl => break PagerAction::Quit,
+                KeyCode::Char(' ' | 'f') | KeyCode::PageDown => {
+                    break PagerAction::NextPage;
+                }
+                KeyCode::Enter | KeyCode::Down | KeyCode::Char('j') => {
+                    break PagerAction::NextLine;
+                }
+                KeyCode::Char('q' | 'Q') | KeyCode::Esc => {
+                    break PagerAction::Quit;
+                }
+                KeyCode::Char('n') => break PagerAction::SearchNext,
+                KeyCode::Char('/') => {
+                    clear_status()?;
+                    match read_pattern()? {
+                        // An empty pattern repeats the last search.
+                        Some(pattern) if pattern.is_empty() => {
+                            break PagerAction::SearchNext;
+                        }
+                        Some(pattern) => break PagerAction::Search(pattern),
+                        None => {
+                            clear_status()?;
+                            print_status(&status)?;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        };
+        clear_status()?;
+
+        Ok(action)
+    }
+}
+
+// ===== helper functions =====
+
+// Returns the number of terminal rows available for each page, reserving one
+// row for the status line.
+fn page_rows() -> usize {
+    let (_, rows) = terminal_size();
+    rows.saturating_sub(1).max(1)
+}
+
+// Returns the number of terminal rows taken by the given line.
+fn line_rows(line: &str, columns: usize) -> usize {
+    display_width(line).div_ceil(columns).max(1)
+}
+
+fn print_status(status: &str) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    queue!(
+        stdout,
+        SetAttribute(Attribute::Reverse),
+        Print(status),
+        SetAttribute(Attribute::Reset)
+    )?;
+    stdout.flush()
+}
+
+fn clear_status() -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    queue!(
+        stdout,
+        cursor::MoveToColumn(0),
+        Clear(ClearType::CurrentLine)
+    )?;
+    stdout.flush()
+}
+
+// Reads a single key press, with the terminal in raw mode.
+fn read_key() -> std::io::Result<event::KeyEvent> {
+    terminal::enable_raw_mode()?;
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                break Ok(key);
+            }
+            Ok(_) => continue,
+            Err(error) => break Err(error),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    result
+}
+
+// Reads a search pattern from the status line. Returns `None` if the search
+// was canceled.
+fn read_pattern() -> std::io::Result<Option<String>> {
+    let mut stdout = std::io::stdout();
+    let mut pattern = String::new();
+
+    queue!(stdout, Print('/'))?;
+    stdout.flush()?;
+    loop {
+        let key = read_key()?;
+        match key.code {
+            KeyCode::Char('c')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                return Ok(None);
+            }
+            KeyCode::Enter => return Ok(Some(pattern)),
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Backspace => {
+                if pattern.pop().is_none() {
+                    return Ok(None);
+                }
+                queue!(stdout, Print("\x08 \x08"))?;
+            }
+            KeyCode::Char(c) => {
+                pattern.push(c);
+                queue!(stdout, Print(c))?;
+            }
+            _ => {}
+        }
+        stdout.flush()?;
+    }
+}
+
+// ===== global functions =====
+
+// Displays the given text through the built-in pager. The text is printed
+// directly when it fits in the terminal or when not running in a terminal.
+pub(crate) fn page(data: &str) -> std::io::Result<()> {
+    let (columns, rows) = terminal_size();
+    if !std::io::stdin().is_terminal()
+        || !std::io::stdout().is_terminal()
+        || data
+            .lines()
+            .map(|line| line_rows(line, columns))
+            .sum::<usize>()
+            < rows
+    {
+        println!("{}", data);
+        return Ok(());
+    }
+
+    Pager::new(data).run()
+}
@@ -4,14 +4,16 @@
 // SPDX-License-Identifier: MIT
 //
 
+use std::borrow::Cow;
 use std::collections::VecDeque;
+use std::ops::RangeInclusive;
 
 use derive_new::new;
 use indextree::NodeId;
 
 use crate::error::ParserError;
 use crate::session::Session;
-use crate::token::{Commands, TokenKind};
+use crate::token::{Commands, Token, TokenKind};
 
 #[derive(Debug, new)]
 pub struct ParsedCommand {
@@ -22,22 +24,182 @@ pub struct ParsedCommand {
 
 pub type ParsedArgs = VecDeque<(String, String)>;
 
+// List key argument, which can be either a plain value or a pattern.
+#[derive(Debug)]
+pub enum KeyArg {
+    Value(String),
+    Pattern(KeyPattern),
+}
+
+// List key pattern matching multiple list entries, such as "eth[0-23]" or "*".
+#[derive(Debug)]
+pub struct KeyPattern {
+    pattern: String,
+    items: Vec<KeyPatternItem>,
+}
+
+#[derive(Debug)]
+enum KeyPatternItem {
+    // Literal character.
+    Char(char),
+    // Any single character ("?").
+    AnyChar,
+    // Any sequence of characters, including the empty one ("*").
+    AnyString,
+    // Decimal number within any of the given ranges ("[1,3,5-7]").
+    Numbers(Vec<RangeInclusive<u64>>),
+}
+
+// ===== impl KeyPattern =====
+
+impl KeyPattern {
+    // Parses a list key argument, which is a plain value unless it contains
+    // any wildcard or range. A backslash makes the next character literal, so
+    // that "eth\*" refers to the "eth*" list entry only.
+    pub(crate) fn parse(word: &str) -> KeyArg {
+        let mut items = vec![];
+        let mut chars = word.char_indices();
+        while let Some((index, c)) = chars.next() {
+            let item = match c {
+                '\\' => match chars.next() {
+                    Some((_, c)) => KeyPatternItem::Char(c),
+                    None => KeyPatternItem::Char(c),
+                },
+                '?' => KeyPatternItem::AnyChar,
+                '*' => KeyPatternItem::AnyString,
+                '[' => match word[index + 1..].split_once(']').and_then(
+                    |(ranges, _)| {
+                        Some((ranges.chars().count(), parse_ranges(ranges)?))
+                    },
+                ) {
+                    Some((len, ranges)) => {
+                        // Skip the range characters and the closing bracket.
+                        chars.nth(len);
+                        KeyPatternItem::Numbers(ranges)
+                    }
+                    None => KeyPatternItem::Char(c),
+                },
+                _ => KeyPatternItem::Char(c),
+            };
+            items.push(item);
+        }
+
+        // Plain list keys aren't patterns.
+        let value = items
+            .iter()
+            .map(|item| match item {
+                KeyPatternItem::Char(c) => Some(*c),
+                _ => None,
+            })
+            .collect::<Option<String>>();
+        if let Some(value) = value {
+            return KeyArg::Value(value);
+        }
+
+        KeyArg::Pattern(KeyPattern {
+            pattern: word.to_owned(),
+            items,
+        })
+    }
+
+    pub(crate) fn matches(&self, value: &str) -> bool {
+        matches_items(&self.items, value)
+    }
+}
+
+impl std::fmt::Display for KeyPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pattern)
+    }
+}
+
 // ===== global functions =====
 
 pub(crate) fn normalize_input_line(line: &str) -> Option<String> {
-    // Ignore "!" comments.
-    // TODO: allow "!" within user input like interface descriptions
-    let line = line.split('!').next()?;
+    let mut normalized = String::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in line.chars() {
+        if quoted {
+            normalized.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => quoted = false,
+                _ => (),
+            }
+            continue;
+        }
 
-    // Remove redundant whitespaces.
-    let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        match c {
+            // Ignore "!" comments.
+            '!' => break,
+            // Remove redundant whitespaces.
+            _ if c.is_whitespace() => {
+                if !normalized.is_empty() && !normalized.ends_with(' ') {
+                    normalized.push(' ');
+                }
+            }
+            '"' => {
+                quoted = true;
+                normalized.push(c);
+            }
+            _ => normalized.push(c),
+        }
+    }
+    let line = normalized.trim_end();
 
     // Handle empty input.
     if line.is_empty() {
         return None;
     }
 
-    Some(line)
+    Some(line.to_owned())
+}
+
+// Splits a command line into words. Double quotes group words containing
+// whitespace or special characters, and backslashes escape the characters
+// that follow them within quotes.
+pub(crate) fn split_words(line: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            _ if c.is_whitespace() => words.extend(word.take()),
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        _ => word.push(c),
+                    }
+                }
+            }
+            _ => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+// Quotes a word if necessary, so that `split_words` returns it unchanged.
+pub(crate) fn quote_word(word: &str) -> Cow<'_, str> {
+    let special = |c: char| c.is_whitespace() || "\"\\{};!#".contains(c);
+    if !word.is_empty() && !word.contains(special) {
+        return Cow::Borrowed(word);
+    }
+
+    let mut quoted = String::from('"');
+    for c in word.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    Cow::Owned(quoted)
 }
 
 fn get_tokens(
@@ -79,15 +241,72 @@ fn find_matching_tokens(
         .collect::<Vec<_>>()
 }
 
-fn find_exact_matching_token<'a>(
+// Picks one of several tokens matching the same word. Keywords take precedence
+// over argument tokens, which match any word, so that a keyword can follow an
+// optional argument regardless of the order in which they are defined. This
+// includes abbreviated keywords, as long as no other keyword matches.
+fn find_preferred_token(
     commands: &Commands,
-    tokens: &'a [NodeId],
+    tokens: &[NodeId],
     word: &str,
-) -> Option<&'a NodeId> {
-    tokens.iter().find(|token_id| {
-        let token = commands.get_token(**token_id);
-        token.matches(word, true)
-    })
+) -> Option<NodeId> {
+    let (keywords, arguments): (Vec<_>, Vec<_>) =
+        tokens.iter().copied().partition(|token_id| {
+            commands.get_token(*token_id).kind == TokenKind::Word
+        });
+
+    // Exact keyword match.
+    if let Some(token_id) = keywords
+        .iter()
+        .find(|token_id| commands.get_token(**token_id).matches(word, true))
+    {
+        return Some(*token_id);
+    }
+
+    // Unique abbreviated keyword.
+    if let [token_id] = keywords[..] {
+        return Some(token_id);
+    }
+
+    // Otherwise, the word is an argument.
+    arguments.first().copied()
+}
+
+fn find_token(
+    commands: &Commands,
+    tokens: Vec<NodeId>,
+    word: &str,
+) -> Result<NodeId, ParserError> {
+    // Find matching tokens.
+    let matching_tokens = find_matching_tokens(commands, tokens, word);
+
+    // Check how many matching tokens were found and return an error if
+    // necessary.
+    match matching_tokens.len() {
+        0 => Err(ParserError::NoMatch),
+        1 => Ok(matching_tokens[0]),
+        _ => {
+            // Try to find a preferred match, otherwise return an ambiguity
+            // error.
+            if let Some(token_id) =
+                find_preferred_token(commands, &matching_tokens, word)
+            {
+                Ok(token_id)
+            } else {
+                Err(ParserError::Ambiguous(matching_tokens))
+            }
+        }
+    }
+}
+
+fn add_argument(args: &mut ParsedArgs, token: &Token, word: &str) {
+    if let Some(argument_name) = &token.argument {
+        let value = match token.kind {
+            TokenKind::Word => token.name.clone(),
+            TokenKind::String | TokenKind::Line => word.to_owned(),
+        };
+        args.push_back((argument_name.clone(), value));
+    }
 }
 
 pub(crate) fn parse_command_try(
@@ -95,39 +314,37 @@ pub(crate) fn parse_command_try(
     commands: &Commands,
     start_token_id: NodeId,
     line: &str,
+) -> Result<ParsedCommand, ParserError> {
+    let configure = session.mode().is_configure();
+    parse_command_tokens(commands, start_token_id, line, configure)
+}
+
+fn parse_command_tokens(
+    commands: &Commands,
+    start_token_id: NodeId,
+    line: &str,
+    configure: bool,
 ) -> Result<ParsedCommand, ParserError> {
     let mut curr_token_id = start_token_id;
     let mut args = ParsedArgs::new();
     let mut negate = false;
 
-    for (index, word) in line.split_whitespace().enumerate() {
+    for (index, word) in split_words(line).iter().enumerate() {
+        // Append the remaining words to the argument of "line" tokens.
+        if commands
+            .get_opt_token(curr_token_id)
+            .is_some_and(|token| token.kind == TokenKind::Line)
+        {
+            let (_, value) = args.back_mut().unwrap();
+            value.push(' ');
+            value.push_str(word);
+            continue;
+        }
+
         let first_word = index == 0;
-        let tokens = get_tokens(
-            commands,
-            curr_token_id,
-            first_word && session.mode().is_configure(),
-        );
-
-        // Find matching tokens.
-        let matching_tokens = find_matching_tokens(commands, tokens, word);
-
-        // Check how many matching tokens were found and return an error if
-        // necessary.
-        let matching_token_id = match matching_tokens.len() {
-            0 => return Err(ParserError::NoMatch),
-            1 => matching_tokens[0],
-            _ => {
-                // Try to find an exact match, otherwise return an ambiguity
-                // error.
-                if let Some(token_id) =
-                    find_exact_matching_token(commands, &matching_tokens, word)
-                {
-                    *token_id
-                } else {
-                    return Err(ParserError::Ambiguous(matching_tokens));
-                }
-            }
-        };
+        let tokens =
+            get_tokens(commands, curr_token_id, first_word && configure);
+        let matching_token_id = find_token(commands, tokens, word)?;
         let matching_token = commands.get_token(matching_token_id);
 
         // Check for negation commands.
@@ -135,13 +352,7 @@ pub(crate) fn parse_command_try(
             negate = true;
         } else {
             // Check for user-provided arguments.
-            if let Some(argument_name) = &matching_token.argument {
-                let value = match matching_token.kind {
-                    TokenKind::Word => matching_token.name.clone(),
-                    TokenKind::String => word.to_owned(),
-                };
-                args.push_back((argument_name.clone(), value));
-            }
+            add_argument(&mut args, matching_token, word);
 
             // Update current token ID and proceed to the next word.
             curr_token_id = matching_token_id;
@@ -157,33 +368,93 @@ pub(crate) fn parse_command_try(
             Err(ParserError::Incomplete(curr_token_id))
         }
     } else {
-        let tokens =
-            get_tokens(commands, start_token_id, session.mode().is_configure());
+        let tokens = get_tokens(commands, start_token_id, configure);
         Err(ParserError::Ambiguous(tokens))
     }
 }
 
+// Parses a (possibly partial) configuration command, returning the last
+// matched token and the collected arguments.
+pub(crate) fn parse_config_path(
+    commands: &Commands,
+    line: &str,
+) -> Result<(NodeId, ParsedArgs), ParserError> {
+    let mut curr_token_id = commands.config_root_yang;
+    let mut args = ParsedArgs::new();
+
+    for word in &split_words(line) {
+        let tokens = get_tokens(commands, curr_token_id, false);
+        let matching_token_id = find_token(commands, tokens, word)?;
+        let matching_token = commands.get_token(matching_token_id);
+        add_argument(&mut args, matching_token, word);
+        curr_token_id = matching_token_id;
+    }
+
+    Ok((curr_token_id, args))
+}
+
 pub(crate) fn parse_command(
     session: &mut Session,
     commands: &Commands,
     line: &str,
 ) -> Result<ParsedCommand, ParserError> {
-    let orig_mode = session.mode().clone();
-    let wd_token_id = orig_mode.token(commands);
+    let (ret, exits) = parse_command_backtrack(session, commands, line, false);
+
+    // Update CLI node when the command was found in upper CLI nodes.
+    if ret.is_ok() {
+        for _ in 0..exits {
+            session.mode_config_exit();
+        }
+    }
+
+    ret
+}
+
+// Parses a command without changing the CLI node, also trying upper CLI nodes
+// like `parse_command` does. Used for interactive features such as syntax
+// highlighting.
+pub(crate) fn parse_command_relaxed(
+    session: &Session,
+    commands: &Commands,
+    line: &str,
+) -> Result<ParsedCommand, ParserError> {
+    let (ret, _) = parse_command_backtrack(session, commands, line, true);
+    ret
+}
 
+// Parses a command in the current CLI node, backtracking to upper CLI nodes
+// if necessary. Returns the result along with the number of CLI nodes to exit
+// for the command to be executed.
+//
+// Backtracking stops once the command is parsed successfully or, in relaxed
+// mode, once any of its words is matched.
+fn parse_command_backtrack(
+    session: &Session,
+    commands: &Commands,
+    line: &str,
+    relaxed: bool,
+) -> (Result<ParsedCommand, ParserError>, usize) {
+    let found = |ret: &Result<ParsedCommand, ParserError>| match relaxed {
+        true => !matches!(ret, Err(ParserError::NoMatch)),
+        false => ret.is_ok(),
+    };
+
+    let wd_token_id = session.mode().token(commands);
     let orig_ret = parse_command_try(session, commands, wd_token_id, line);
-    if orig_ret.is_ok() {
-        return orig_ret;
+    if found(&orig_ret) {
+        return (orig_ret, 0);
     }
 
     // Back-tracking: check if the command is present in upper CLI nodes.
+    let mut exits = 0;
     let mut token_id_child = wd_token_id;
     for token_id in wd_token_id.ancestors(&commands.arena) {
         // Update CLI node when traversing a YANG list.
-        if let Some(token) = commands.get_opt_token(token_id) {
-            if token.node_update {
-                session.mode_config_exit();
-            }
+        if commands
+            .get_opt_token(token_id)
+            .is_some_and(|token| token.node_update)
+        {
+            exits += 1;
         }
         // Ignore list keys that can match on everything.
         match commands.get_opt_token(token_id_child) {
@@ -200,12 +471,280 @@ pub(crate) fn parse_command(
 
         // Try the same command in this CLI node.
         let ret = parse_command_try(session, commands, token_id, line);
-        if ret.is_ok() {
-            return ret;
+        if found(&ret) {
+            return (ret, exits);
+        }
+    }
+
+    // Return the original error.
+    (orig_ret, 0)
+}
+
+// ===== helper functions =====
+
+// Parses a comma-separated list of numbers and number ranges.
+fn parse_ranges(ranges: &str) -> Option<Vec<RangeInclusive<u64>>> {
+    ranges
+        .split(',')
+        .map(|range| match range.split_once('-') {
+            Some((start, end)) => {
+                let start = start.trim().parse::<u64>().ok()?;
+                let end = end.trim().parse::<u64>().ok()?;
+                (start <= end).then_some(start..=end)
+            }
+            None => {
+                let number = range.trim().parse::<u64>().ok()?;
+                Some(number..=number)
+            }
+        })
+        .collect()
+}
+
+fn matches_items(items: &[KeyPatternItem], value: &str) -> bool {
+    let Some((item, items)) = items.split_first() else {
+        return value.is_empty();
+    };
+
+    match item {
+        KeyPatternItem::Char(c) => value
+            .strip_prefix(*c)
+            .is_some_and(|value| matches_items(items, value)),
+        KeyPatternItem::AnyChar => {
+            let mut chars = value.chars();
+            chars.next().is_some() && matches_items(items, chars.as_str())
+        }
+        KeyPatternItem::AnyString => value
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(std::iter::once(value.len()))
+            .any(|index| matches_items(items, &value[index..])),
+        KeyPatternItem::Numbers(ranges) => {
+            let digits =
+                value.chars().take_while(|c| c.is_ascii_digit()).count();
+            (1..=digits).any(|len| {
+                value[..len].parse::<u64>().is_ok_and(|number| {
+                    ranges.iter().any(|range| range.contains(&number))
+                }) && matches_items(items, &value[len..])
+            })
         }
     }
+}
+
+// ===== unit tests =====
 
-    // Restore original CLI node and return the original error.
-    session.mode_set(orig_mode);
-    orig_ret
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::string_pairs;
+    use crate::token_xml;
+
+    fn parse(line: &str, configure: bool) -> Vec<(String, String)> {
+        let mut commands = Commands::new();
+        token_xml::gen_cmds(&mut commands);
+        let start_token_id = match configure {
+            true => commands.config_root_yang,
+            false => commands.exec_root,
+        };
+        let pcmd =
+            parse_command_tokens(&commands, start_token_id, line, configure)
+                .unwrap();
+        pcmd.args.into_iter().collect()
+    }
+
+    #[test]
+    fn show_config_keywords() {
+        assert_eq!(
+            parse("show running format json", false),
+            string_pairs(&[("configuration", "running"), ("format", "json")])
+        );
+        assert_eq!(
+            parse("show running format json", true),
+            string_pairs(&[("configuration", "running"), ("format", "json")])
+        );
+        assert_eq!(
+            parse("show candidate format xml", true),
+            string_pairs(&[("configuration", "candidate"), ("format", "xml")])
+        );
+        assert_eq!(
+            parse("show running with-defaults", true),
+            string_pairs(&[
+                ("configuration", "running"),
+                ("with-defaults", "with-defaults")
+            ])
+        );
+    }
+
+    #[test]
+    fn show_config_abbreviated_keywords() {
+        assert_eq!(
+            parse("show running form json", true),
+            string_pairs(&[("configuration", "running"), ("format", "json")])
+        );
+        assert_eq!(
+            parse("show candidate fo x", true),
+            string_pairs(&[("configuration", "candidate"), ("format", "xml")])
+        );
+        assert_eq!(
+            parse("show running with", true),
+            string_pairs(&[
+                ("configuration", "running"),
+                ("with-defaults", "with-defaults")
+            ])
+        );
+        assert_eq!(
+            parse("show running form json with", false),
+            string_pairs(&[
+                ("configuration", "running"),
+                ("format", "json"),
+                ("with-defaults", "with-defaults")
+            ])
+        );
+    }
+
+    fn pattern(word: &str) -> KeyPattern {
+        match KeyPattern::parse(word) {
+            KeyArg::Pattern(pattern) => pattern,
+            KeyArg::Value(value) => panic!("not a pattern: {}", value),
+        }
+    }
+
+    #[test]
+    fn key_pattern_values() {
+        for (word, expected) in [
+            ("eth0", "eth0"),
+            ("eth[0", "eth[0"),
+            ("eth[a-b]", "eth[a-b]"),
+            (r"eth\*", "eth*"),
+            (r"eth\?\[1]", "eth?[1]"),
+            (r"a\\b", r"a\b"),
+        ] {
+            match KeyPattern::parse(word) {
+                KeyArg::Value(value) => assert_eq!(value, expected),
+                KeyArg::Pattern(_) => panic!("unexpected pattern: {}", word),
+            }
+        }
+    }
+
+    #[test]
+    fn key_pattern_matches() {
+        let eth = pattern("eth[0-3,8]");
+        assert!(eth.matches("eth0"));
+        assert!(eth.matches("eth3"));
+        assert!(eth.matches("eth8"));
+        assert!(!eth.matches("eth4"));
+        assert!(!eth.matches("eth10"));
+        assert!(!eth.matches("eth"));
+
+        let any = pattern("*");
+        assert!(any.matches(""));
+        assert!(any.matches("eth0"));
+
+        let suffix = pattern("*.[10-20]");
+        assert!(suffix.matches("eth0.10"));
+        assert!(suffix.matches("a.b.15"));
+        assert!(!suffix.matches("eth0.21"));
+
+        let single = pattern("eth?");
+        assert!(single.matches("eth0"));
+        assert!(!single.matches("eth"));
+        assert!(!single.matches("eth10"));
+
+        let escaped = pattern(r"\**");
+        assert!(escaped.matches("*eth0"));
+        assert!(!escaped.matches("eth0"));
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(parse_ranges("1"), Some(vec![1..=1]));
+        assert_eq!(parse_ranges("1,3-5, 7"), Some(vec![1..=1, 3..=5, 7..=7]));
+        assert_eq!(parse_ranges("5-3"), None);
+        assert_eq!(parse_ranges("a"), None);
+        assert_eq!(parse_ranges(""), None);
+    }
+
+    #[test]
+    fn match_items() {
+        use KeyPatternItem::*;
+
+        assert!(matches_items(&[], ""));
+        assert!(!matches_items(&[], "a"));
+        assert!(matches_items(&[Char('a'), AnyString, Char('z')], "abcz"));
+        assert!(!matches_items(&[Char('a'), AnyString, Char('z')], "abc"));
+        // Numbers match the longest prefix within range only if the rest of
+        // the value matches as well.
+        let items = [Numbers(vec![1..=12]), Char('2')];
+        assert!(matches_items(&items, "12"));
+        assert!(matches_items(&items, "122"));
+        assert!(!matches_items(&items, "13"));
+    }
+
+    #[test]
+    fn quoted_words() {
+        assert_eq!(split_words(" a  b\tc "), ["a", "b", "c"]);
+        assert_eq!(
+            split_words(r#"description "a b" "x\"y\\z" """#),
+            ["description", "a b", "x\"y\\z", ""]
+        );
+        for word in ["eth0", "a b", "x\"y", "{;}", "!", ""] {
+            assert_eq!(split_words(&quote_word(word)), [word]);
+        }
+        assert_eq!(quote_word("eth0"), "eth0");
+        assert_eq!(quote_word("a b"), "\"a b\"");
+    }
+
+    #[test]
+    fn normalize_line() {
+        assert_eq!(normalize_input_line("  a   b  ! c").unwrap(), "a b");
+        assert_eq!(
+            normalize_input_line(r#"a "b  !  c"  d"#).unwrap(),
+            r#"a "b  !  c" d"#
+        );
+        assert_eq!(normalize_input_line(" ! comment"), None);
+    }
+
+    #[test]
+    fn show_history() {
+        assert_eq!(
+            parse("show history 20", false),
+            string_pairs(&[("count", "20")])
+        );
+        assert_eq!(
+            parse("show history search interface eth0", false),
+            string_pairs(&[("text", "interface eth0")])
+        );
+        assert_eq!(parse("show history edits", true), vec![]);
+        assert_eq!(
+            parse("show history 5", true),
+            string_pairs(&[("count", "5")])
+        );
+    }
+
+    #[test]
+    fn abbreviated_keywords_before_arguments() {
+        assert_eq!(
+            parse("show history sea foo", false),
+            string_pairs(&[("text", "foo")])
+        );
+        assert_eq!(parse("show history e", true), vec![]);
+        assert_eq!(
+            parse("restore auto", true),
+            string_pairs(&[("autosave", "autosave")])
+        );
+        assert_eq!(
+            parse("restore before-bgp", true),
+            string_pairs(&[("name", "before-bgp")])
+        );
+    }
+
+    #[test]
+    fn show_config_path() {
+        assert_eq!(
+            parse("show running interfaces interface eth0", true),
+            string_pairs(&[
+                ("configuration", "running"),
+                ("path", "interfaces interface eth0")
+            ])
+        );
+    }
 }
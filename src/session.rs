@@ -4,33 +4,63 @@
 // SPDX-License-Identifier: MIT
 //
 
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, TimeDelta};
 use derive_new::new;
 use enum_as_inner::EnumAsInner;
 use indextree::NodeId;
 use yang3::data::{
-    Data, DataFormat, DataParserFlags, DataTree, DataValidationFlags,
+    Data, DataDiffFlags, DataFormat, DataParserFlags, DataPrinterFlags,
+    DataTree, DataValidationFlags,
 };
 use yang3::schema::{SchemaNode, SchemaNodeKind};
 
 use crate::client::{Client, DataType, DataValue};
 use crate::error::Error;
-use crate::parser::ParsedArgs;
+use crate::parser::{KeyArg, KeyPattern, ParsedArgs};
 use crate::token::Commands;
+use crate::user_config::Settings;
 use crate::{token_yang, YANG_CTX};
 
 static DEFAULT_HOSTNAME: &str = "holo";
+static DEFAULT_EDIT_HISTORY_SIZE: usize = 50;
 
 #[derive(Debug)]
 pub struct Session {
     hostname: String,
     prompt: String,
-    use_pager: bool,
+    settings: Settings,
+    terminal_update: bool,
     mode: CommandMode,
     running: DataTree<'static>,
+    // Running configuration fetched after a reconnection, when the
+    // uncommitted changes couldn't be rebased on top of it.
+    running_pending: Option<DataTree<'static>>,
     candidate: Option<DataTree<'static>>,
+    edits_undo: VecDeque<CandidateEdit>,
+    edits_redo: Vec<CandidateEdit>,
+    // Groups of edits in progress, innermost last. The outermost group is
+    // recorded as a single edit once complete.
+    edit_groups: Vec<CandidateEdit>,
+    checkpoints: BTreeMap<String, DataTree<'static>>,
+    autosave: bool,
+    autosaved: Option<DataTree<'static>>,
+    candidate_dirty: bool,
+    commit_confirm_deadline: Option<DateTime<Local>>,
     client: Box<dyn Client>,
 }
 
+// Candidate configuration edit, along with a snapshot of the candidate
+// configuration taken before the edit.
+#[derive(Debug)]
+pub struct CandidateEdit {
+    pub cli_path: String,
+    pub command: String,
+    candidate: DataTree<'static>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, EnumAsInner)]
 pub enum CommandMode {
     Operational,
@@ -44,6 +74,17 @@ pub struct CommandNode {
     data_path: String,
 }
 
+// Outcome of the resynchronization with the daemon after a reconnection.
+#[derive(Debug)]
+pub enum ReconnectStatus {
+    // The running configuration was fetched again. The uncommitted changes,
+    // if any, were rebased on top of it unless they conflict with it.
+    Resynchronized { rebased: bool },
+    // The YANG modules supported by the daemon changed, hence the CLI needs
+    // to be restarted.
+    ModulesChanged,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum ConfigurationType {
     Running,
@@ -53,12 +94,39 @@ pub enum ConfigurationType {
 // ===== impl Session =====
 
 impl Session {
-    pub(crate) fn new(use_pager: bool, mut client: Box<dyn Client>) -> Session {
+    pub(crate) fn new(
+        settings: Settings,
+        mut client: Box<dyn Client>,
+    ) -> Session {
+        let running = Session::fetch_running(client.as_mut()).unwrap();
+
+        Session {
+            hostname: DEFAULT_HOSTNAME.to_owned(),
+            prompt: String::new(),
+            settings,
+            terminal_update: false,
+            mode: CommandMode::Operational,
+            running,
+            running_pending: None,
+            candidate: None,
+            edits_undo: Default::default(),
+            edits_redo: Default::default(),
+            edit_groups: Default::default(),
+            checkpoints: Default::default(),
+            autosave: false,
+            autosaved: None,
+            candidate_dirty: false,
+            commit_confirm_deadline: None,
+            client,
+        }
+    }
+
+    fn fetch_running(
+        client: &mut dyn Client,
+    ) -> Result<DataTree<'static>, Error> {
         let yang_ctx = YANG_CTX.get().unwrap();
         let data_format = DataFormat::LYB;
-        let running = client
-            .get(DataType::Config, data_format, false, None)
-            .unwrap();
+        let running = client.get(DataType::Config, data_format, false, None)?;
         let running = DataTree::parse_string(
             yang_ctx,
             running.as_bytes(),
@@ -67,16 +135,59 @@ impl Session {
             DataValidationFlags::PRESENT | DataValidationFlags::NO_STATE,
         )
         .expect("Failed to parse data tree");
+        Ok(running)
+    }
 
-        Session {
-            hostname: DEFAULT_HOSTNAME.to_owned(),
-            prompt: String::new(),
-            use_pager,
-            mode: CommandMode::Operational,
-            running,
-            candidate: None,
-            client,
+    // Resynchronizes the session after the connection to the daemon was
+    // reestablished, if that's the case.
+    pub(crate) fn reconnect_check(
+        &mut self,
+    ) -> Result<Option<ReconnectStatus>, Error> {
+        if !self.client.reconnected() {
+            return Ok(None);
+        }
+
+        // The YANG context can't be changed once initialized.
+        if self.client.modules_changed()? {
+            return Ok(Some(ReconnectStatus::ModulesChanged));
+        }
+
+        // Fetch the running configuration again, as it might have changed
+        // while disconnected.
+        let running = Session::fetch_running(self.client.as_mut())?;
+
+        // Rebase the uncommitted changes on top of the new running
+        // configuration.
+        if let Some(candidate) = &self.candidate {
+            let candidate = self
+                .running
+                .diff(candidate, DataDiffFlags::DEFAULTS)
+                .and_then(|changes| {
+                    let mut candidate = running.duplicate()?;
+                    candidate.diff_apply(&changes)?;
+                    Ok(candidate)
+                });
+            match candidate {
+                Ok(candidate) => {
+                    self.candidate_replace("rebase".to_owned(), candidate)
+                }
+                Err(_) => {
+                    // Keep the uncommitted changes relative to the previous
+                    // running configuration until they're either committed
+                    // or discarded.
+                    self.running_pending = Some(running);
+                    return Ok(Some(ReconnectStatus::Resynchronized {
+                        rebased: false,
+                    }));
+                }
+            }
         }
+        self.running = running;
+        self.running_pending = None;
+        self.candidate_dirty_update();
+        self.update_hostname();
+
+        Ok(Some(ReconnectStatus::Resynchronized { rebased: true }))
     }
 
     pub(crate) fn update_hostname(&mut self) {
@@ -89,12 +200,80 @@ impl Session {
         self.update_prompt();
     }
 
+    pub(crate) fn hostname(&self) -> &str {
+        &self.hostname
+    }
+
     pub(crate) fn prompt(&self) -> String {
         self.prompt.clone()
     }
 
     pub(crate) fn use_pager(&self) -> bool {
-        self.use_pager
+        self.settings.pager()
+    }
+
+    pub(crate) fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub(crate) fn settings_set(
+        &mut self,
+        option: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        self.settings.set(option, value)?;
+
+        // Some settings require the line editor to be reinitialized.
+        if matches!(option, "colors" | "edit-mode" | "history-file") {
+            self.terminal_update = true;
+        }
+
+        Ok(())
+    }
+
+    // Returns whether the line editor needs to be reinitialized, clearing the
+    // flag.
+    pub(crate) fn terminal_update_take(&mut self) -> bool {
+        std::mem::take(&mut self.terminal_update)
+    }
+
+    // Path of the command history file of the given mode. Unless a history
+    // file is configured, histories are kept per target device. The exec and
+    // configuration modes have separate histories.
+    pub(crate) fn history_path(&self, configure: bool) -> Option<PathBuf> {
+        let mode = if configure { "config" } else { "exec" };
+        match self.settings.history_file() {
+            Some(file) if configure => {
+                let mut file = file.as_os_str().to_owned();
+                file.push(".");
+                file.push(mode);
+                Some(file.into())
+            }
+            Some(file) => Some(file.to_owned()),
+            None => {
+                let dir = crate::state_dir()?.join("history");
+                Some(dir.join(format!("{}.{}", self.target_name(), mode)))
+            }
+        }
+    }
+
+    // Returns the daemon address in a form suitable for file names.
+    fn target_name(&self) -> String {
+        let address = self.settings.address();
+        address
+            .split_once("://")
+            .map_or(address, |(_, target)| target)
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' => c,
+                _ => '_',
+            })
+            .collect()
+    }
+
+    // Enables saving the candidate configuration to disk after each edit.
+    pub(crate) fn autosave_enable(&mut self) {
+        self.autosave = true;
     }
 
     fn update_prompt(&mut self) {
@@ -105,11 +284,27 @@ impl Session {
                     Some(node) => &node.cli_path,
                     None => "",
                 };
+
                 format!("{}(config{})", self.hostname, path)
             }
         }
     }
 
+    // Returns the banner identifying the current configuration node, in the
+    // style of "[edit interfaces interface eth0]".
+    pub(crate) fn edit_banner(&self) -> Option<String> {
+        let nodes = self.mode.as_configure()?;
+        let path = match nodes.last() {
+            Some(node) => &node.cli_path,
+            None => "",
+        };
+        let edit = path
+            .split(['/', '[', ']'])
+            .filter(|word| !word.is_empty())
+            .fold(String::new(), |edit, word| edit + " " + word);
+        Some(format!("[edit{}]", edit))
+    }
+
     pub(crate) fn mode(&self) -> &CommandMode {
         &self.mode
     }
@@ -122,8 +317,20 @@ impl Session {
         // Create/delete candidate configuration if necessary.
         if mode.is_configure() && self.mode.is_operational() {
             self.candidate = Some(self.running.duplicate().unwrap());
+            self.autosaved = self.autosave_load();
         } else if mode.is_operational() && self.mode.is_configure() {
+            if let Some(running) = self.running_pending.take() {
+                self.running = running;
+                self.update_hostname();
+            }
             self.candidate = None;
+            self.candidate_dirty = false;
+            self.edits_undo.clear();
+            self.edits_redo.clear();
+            self.checkpoints.clear();
+            self.autosaved = None;
+            self.edit_groups.clear();
+            self.autosave_remove();
         }
 
         self.mode = mode;
@@ -139,21 +346,29 @@ impl Session {
     pub(crate) fn mode_config_exit(&mut self) {
         let nodes = self.mode.as_configure_mut().unwrap();
         if nodes.pop().is_none() {
-            self.mode = CommandMode::Operational
+            self.mode_set(CommandMode::Operational);
         }
         self.update_prompt();
     }
 
     pub(crate) fn edit_candidate(
         &mut self,
+        command: &str,
         negate: bool,
         snode: &SchemaNode<'_>,
         mut args: ParsedArgs,
     ) -> Result<(), yang3::Error> {
         // Get data path and CLI path corresponding to the current node.
-        let mut path = self.mode.data_path().unwrap_or_default();
+        let path = self.mode.data_path().unwrap_or_default();
         let mut cli_path = self.mode.cli_path().unwrap_or_default();
 
+        // Take a snapshot of the candidate configuration for undo purposes.
+        let edit = CandidateEdit {
+            cli_path: cli_path.clone(),
+            command: command.to_owned(),
+            candidate: self.candidate.as_ref().unwrap().duplicate().unwrap(),
+        };
+
         // Create list of schema nodes, ordered from parent to child.
         let mut snodes = vec![];
         if !snode.is_list_key() {
@@ -162,6 +377,14 @@ impl Session {
             snodes.extend(snode.ancestors());
         }
 
+        // Data paths of all nodes being edited. List key patterns can make a
+        // single command apply to multiple list entries.
+        let mut paths = vec![path];
+        let mut bulk = false;
+
+        // CLI nodes to enter, once the edit succeeds.
+        let mut nodes = vec![];
+
         // Iterate over all schema nodes starting from the root.
         let mut skip = self.mode.as_configure().unwrap().len();
         for snode in snodes.iter().filter(|snode| !snode.is_schema_only()).rev()
@@ -177,17 +400,36 @@ impl Session {
                 }
             }
 
-            // Update data path.
-            path += &format!("/{}:{}", snode.module().name(), snode.name());
+            // Update data paths.
+            for path in &mut paths {
+                *path +=
+                    &format!("/{}:{}", snode.module().name(), snode.name());
+            }
             let mut list_keys = ParsedArgs::new();
             for snode in snode.list_keys() {
-                let arg = args.pop_front().unwrap();
-                path += &format!("[{}='{}']", snode.name(), arg.1);
-                list_keys.push_back(arg);
+                let (name, value) = args.pop_front().unwrap();
+                let value = match KeyPattern::parse(&value) {
+                    KeyArg::Pattern(pattern) => {
+                        let candidate = self.candidate.as_ref().unwrap();
+                        paths = list_entries_matching(
+                            candidate, &paths, &snode, &pattern,
+                        )?;
+                        bulk = true;
+                        value
+                    }
+                    KeyArg::Value(value) => {
+                        for path in &mut paths {
+                            *path += &path_predicate(snode.name(), &value);
+                        }
+                        value
+                    }
+                };
+                list_keys.push_back((name, value));
             }
 
             // Update CLI node.
             if !negate
+                && !bulk
                 && (snode.kind() == SchemaNodeKind::List || snode.is_list_key())
             {
                 let snode = match snode.list_keys().last() {
@@ -196,12 +438,26 @@ impl Session {
                 };
                 let token_id = token_yang::snode_get_token_id(&snode);
                 token_yang::update_cli_path(&mut cli_path, &snode, &list_keys);
-                let node =
-                    CommandNode::new(token_id, cli_path.clone(), path.clone());
-                self.mode_config_enter(node);
+                nodes.push(CommandNode::new(
+                    token_id,
+                    cli_path.clone(),
+                    paths[0].clone(),
+                ));
             }
         }
 
+        // Multiple list entries can be edited at once, but not entered.
+        if bulk && !negate && snode.is_list_key() {
+            return Err(yang3::Error {
+                msg: Some(
+                    "list key patterns can only be used to edit nodes below \
+                     the list entries"
+                        .to_owned(),
+                ),
+                ..Default::default()
+            });
+        }
+
         // Get leaf/leaf-list's value.
         let value = match snode.kind() {
             yang3::schema::SchemaNodeKind::Leaf
@@ -220,21 +476,269 @@ impl Session {
         // Ensure all arguments were processed.
         assert_eq!(args.len(), 0);
 
-        // Edit the candidate configuration.
+        // Edit the candidate configuration, restoring its previous state in
+        // case any of the edits fails.
         let candidate = self.candidate.as_mut().unwrap();
-        if negate {
-            if candidate.find_path(&path).is_ok() {
-                candidate.remove(&path)?;
+        for path in paths {
+            if let Err(error) =
+                edit_candidate_path(candidate, snode, negate, path, &value)
+            {
+                *candidate = edit.candidate;
+                return Err(error);
             }
-        } else {
-            candidate.new_path(&path, value.as_deref(), false)?;
+        }
+
+        // Record the edit in the history.
+        self.edit_history_push(edit);
+
+        // Update CLI node.
+        for node in nodes {
+            self.mode_config_enter(node);
         }
 
         Ok(())
     }
 
     pub(crate) fn candidate_discard(&mut self) {
-        self.candidate = Some(self.running.duplicate().unwrap());
+        if let Some(running) = self.running_pending.take() {
+            self.running = running;
+            self.update_hostname();
+        }
+        let candidate = self.running.duplicate().unwrap();
+        let candidate = self.candidate.replace(candidate).unwrap();
+        self.edit_history_push(CandidateEdit {
+            cli_path: String::new(),
+            command: "discard".to_owned(),
+            candidate,
+        });
+    }
+
+    // Starts a group of edits, which is recorded in the edit history as a
+    // single edit. Groups can be nested.
+    pub(crate) fn edit_group_begin(&mut self, command: &str) {
+        self.edit_groups.push(CandidateEdit {
+            cli_path: self.mode.cli_path().unwrap_or_default(),
+            command: command.to_owned(),
+            candidate: self.candidate.as_ref().unwrap().duplicate().unwrap(),
+        });
+    }
+
+    // Ends the current group of edits. On rollback, all edits made since the
+    // group started are reverted.
+    pub(crate) fn edit_group_end(&mut self, rollback: bool) {
+        let Some(edit) = self.edit_groups.pop() else {
+            return;
+        };
+        let Some(candidate) = self.candidate.as_mut() else {
+            return;
+        };
+        if rollback {
+            *candidate = edit.candidate;
+        } else {
+            self.edit_history_push(edit);
+        }
+    }
+
+    fn edit_history_push(&mut self, edit: CandidateEdit) {
+        // Edits within a group are recorded once the outermost group ends.
+        if !self.edit_groups.is_empty() {
+            return;
+        }
+
+        self.edits_undo.push_back(edit);
+        if self.edits_undo.len() > DEFAULT_EDIT_HISTORY_SIZE {
+            self.edits_undo.pop_front();
+        }
+        self.edits_redo.clear();
+        self.candidate_dirty_update();
+        self.autosave();
+    }
+
+    // Reverts up to `count` candidate edits, returning the number of edits
+    // that were reverted.
+    pub(crate) fn candidate_undo(&mut self, count: usize) -> usize {
+        let mut reverted = 0;
+        while reverted < count {
+            let Some(mut edit) = self.edits_undo.pop_back() else {
+                break;
+            };
+            std::mem::swap(
+                self.candidate.as_mut().unwrap(),
+                &mut edit.candidate,
+            );
+            self.edits_redo.push(edit);
+            reverted += 1;
+        }
+        if reverted > 0 {
+            self.candidate_dirty_update();
+            self.autosave();
+        }
+        reverted
+    }
+
+    // Reapplies the last reverted candidate edit, returning whether there was
+    // anything to redo.
+    pub(crate) fn candidate_redo(&mut self) -> bool {
+        let Some(mut edit) = self.edits_redo.pop() else {
+            return false;
+        };
+        std::mem::swap(self.candidate.as_mut().unwrap(), &mut edit.candidate);
+        self.edits_undo.push_back(edit);
+        self.candidate_dirty_update();
+        self.autosave();
+        true
+    }
+
+    pub(crate) fn candidate_checkpoint(&mut self, name: &str) {
+        let candidate = self.candidate.as_ref().unwrap().duplicate().unwrap();
+        self.checkpoints.insert(name.to_owned(), candidate);
+    }
+
+    // Replaces the candidate configuration with a previously saved
+    // checkpoint, returning whether the checkpoint exists.
+    pub(crate) fn candidate_restore(&mut self, name: &str) -> bool {
+        let Some(checkpoint) = self.checkpoints.get(name) else {
+            return false;
+        };
+        let candidate = checkpoint.duplicate().unwrap();
+        self.candidate_replace(format!("restore {}", name), candidate);
+        true
+    }
+
+    pub(crate) fn candidate_checkpoints(
+        &self,
+    ) -> impl Iterator<Item = &String> {
+        self.checkpoints.keys()
+    }
+
+    // Replaces the candidate configuration with the uncommitted changes left
+    // by a previous session, returning whether there were any.
+    pub(crate) fn candidate_restore_autosave(&mut self) -> bool {
+        let Some(candidate) = self.autosaved.take() else {
+            return false;
+        };
+        self.candidate_replace("restore autosave".to_owned(), candidate);
+        true
+    }
+
+    pub(crate) fn candidate_autosaved(&self) -> bool {
+        self.autosaved.is_some()
+    }
+
+    fn candidate_replace(
+        &mut self,
+        command: String,
+        candidate: DataTree<'static>,
+    ) {
+        let candidate = self.candidate.replace(candidate).unwrap();
+        self.edit_history_push(CandidateEdit {
+            cli_path: String::new(),
+            command,
+            candidate,
+        });
+    }
+
+    // Path of the file where the candidate configuration is saved after each
+    // edit, so that uncommitted changes can be recovered after an unexpected
+    // termination.
+    pub(crate) fn autosave_path(&self) -> Option<PathBuf> {
+        if !self.autosave {
+            return None;
+        }
+        let dir = crate::state_dir()?;
+        Some(dir.join(format!(
+            "candidate-{}-{}.json",
+            self.hostname,
+            self.target_name()
+        )))
+    }
+
+    fn autosave(&self) {
+        let Some(path) = self.autosave_path() else {
+            return;
+        };
+        let candidate = self.candidate.as_ref().unwrap();
+        // Use JSON rather than LYB, which depends on the YANG context, so that
+        // the changes can still be restored after the modules are reloaded.
+        let Ok(data) = candidate
+            .print_bytes(DataFormat::JSON, DataPrinterFlags::WITH_SIBLINGS)
+        else {
+            return;
+        };
+
+        // Failing to save the candidate isn't fatal, so errors are ignored.
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let _ = write_private_file(&path, &data);
+    }
+
+    // Loads the candidate configuration saved by a previous session, if it
+    // differs from the running configuration.
+    fn autosave_load(&self) -> Option<DataTree<'static>> {
+        let path = self.autosave_path()?;
+        let data = std::fs::read(path).ok()?;
+        let yang_ctx = YANG_CTX.get().unwrap();
+        let candidate = DataTree::parse_string(
+            yang_ctx,
+            &data,
+            DataFormat::JSON,
+            DataParserFlags::NO_VALIDATION,
+            DataValidationFlags::empty(),
+        )
+        .ok()?;
+        let diff = self
+            .running
+            .diff(&candidate, DataDiffFlags::DEFAULTS)
+            .ok()?;
+        let changed = diff.iter().next().is_some();
+        changed.then_some(candidate)
+    }
+
+    // Takes over the candidate configuration saved by another process, such
+    // as the one that restarted this one, whose file name might differ (e.g.
+    // if the hostname changed in the meantime).
+    pub(crate) fn autosave_adopt(&self, path: &Path) {
+        let Some(autosave_path) = self.autosave_path() else {
+            return;
+        };
+        if autosave_path != path {
+            let _ = std::fs::rename(path, autosave_path);
+        }
+    }
+
+    fn autosave_remove(&self) {
+        if let Some(path) = self.autosave_path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    pub(crate) fn candidate_edits(
+        &self,
+    ) -> impl Iterator<Item = &CandidateEdit> {
+        self.edits_undo.iter()
+    }
+
+    // Returns whether the candidate configuration has uncommitted changes.
+    pub(crate) fn candidate_dirty(&self) -> bool {
+        self.candidate_dirty
+    }
+
+    // Returns when the pending confirmed commit, if any, will be rolled back.
+    pub(crate) fn commit_confirm_deadline(&self) -> Option<DateTime<Local>> {
+        self.commit_confirm_deadline
+            .filter(|deadline| *deadline > Local::now())
+    }
+
+    // Compares the candidate and running configurations. Done once after
+    // each change, so that checking for uncommitted changes is cheap.
+    fn candidate_dirty_update(&mut self) {
+        self.candidate_dirty =
+            self.candidate.as_ref().is_some_and(|candidate| {
+                self.running
+                    .diff(candidate, DataDiffFlags::DEFAULTS)
+                    .is_ok_and(|diff| diff.iter().next().is_some())
+            });
     }
 
     pub(crate) fn candidate_validate(&mut self) -> Result<(), Error> {
@@ -247,10 +751,24 @@ impl Session {
         self.client.validate_candidate(candidate)
     }
 
+    // Commits the candidate configuration. When a confirmation timeout (in
+    // minutes) is given, the device rolls back the changes unless another
+    // commit is performed before the timeout expires.
     pub(crate) fn candidate_commit(
         &mut self,
         comment: Option<String>,
+        confirmed_timeout: Option<u32>,
     ) -> Result<(), Error> {
+        // Changes that couldn't be rebased after a reconnection are relative
+        // to an outdated running configuration.
+        if self.running_pending.is_some() {
+            return Err(Error::Callback(
+                "uncommitted changes are based on an outdated running \
+                 configuration, use \"discard\" to drop them"
+                    .to_owned(),
+            ));
+        }
+
         let candidate = self.candidate.as_mut().unwrap();
 
         // Validate the candidate configuration against YANG schema first.
@@ -258,11 +776,24 @@ impl Session {
 
         // Request the device to validate and commit the candidate
         // configuration.
-        self.client
-            .commit_candidate(&self.running, candidate, comment)?;
+        self.client.commit_candidate(
+            &self.running,
+            candidate,
+            comment,
+            confirmed_timeout.unwrap_or(0),
+        )?;
+
+        // Keep track of pending confirmed commits.
+        self.commit_confirm_deadline = confirmed_timeout.map(|minutes| {
+            Local::now() + TimeDelta::minutes(i64::from(minutes))
+        });
 
         // Replace the running configuration with the candidate configuration.
         self.running = candidate.duplicate().unwrap();
+        self.candidate_dirty = false;
+
+        // The saved candidate configuration isn't needed anymore.
+        self.autosave_remove();
 
         // Fetch hostname from running configuration and update the prompt.
         self.update_hostname();
@@ -279,7 +810,7 @@ impl Session {
     }
 
     pub(crate) fn get_configuration(
-        &mut self,
+        &self,
         config_type: ConfigurationType,
     ) -> &DataTree<'static> {
         match config_type {
@@ -288,6 +819,10 @@ impl Session {
         }
     }
 
+    pub(crate) fn version(&mut self) -> Result<String, Error> {
+        self.client.version()
+    }
+
     pub(crate) fn get(
         &mut self,
         data_type: DataType,
@@ -297,6 +832,14 @@ impl Session {
     ) -> Result<DataValue, Error> {
         self.client.get(data_type, format, with_defaults, xpath)
     }
+
+    pub(crate) fn get_transaction(
+        &mut self,
+        transaction_id: u32,
+        format: DataFormat,
+    ) -> Result<DataValue, Error> {
+        self.client.get_transaction(transaction_id, format)
+    }
 }
 
 // ===== impl CommandMode =====
@@ -330,3 +873,141 @@ impl CommandMode {
         }
     }
 }
+
+// ===== helper functions =====
+
+fn edit_candidate_path(
+    candidate: &mut DataTree<'static>,
+    snode: &SchemaNode<'_>,
+    negate: bool,
+    path: String,
+    value: &Option<String>,
+) -> Result<(), yang3::Error> {
+    if !negate {
+        candidate.new_path(&path, value.as_deref(), false)?;
+        return Ok(());
+    }
+
+    match (snode.kind(), value) {
+        // Remove only the given leaf-list entry.
+        (SchemaNodeKind::LeafList, Some(value)) => {
+            let value = canonical_value(&path, value)?;
+            let entry = candidate
+                .find_xpath(&path)?
+                .find(|dnode| dnode.value_canonical().as_ref() == Some(&value))
+                .map(|dnode| dnode.path());
+            let Some(entry) = entry else {
+                return Err(value_not_configured(path, &value));
+            };
+            candidate.remove(&entry)?;
+            return Ok(());
+        }
+        // Refuse to remove a leaf that isn't set or whose value doesn't
+        // match.
+        (SchemaNodeKind::Leaf, Some(value)) => {
+            let Ok(dnode) = candidate.find_path(&path) else {
+                return Err(value_not_configured(path, value));
+            };
+            let current = dnode.value_canonical();
+            if current != Some(canonical_value(&path, value)?) {
+                return Err(yang3::Error {
+                    msg: Some(format!("value mismatch: {}", value)),
+                    path: Some(path),
+                    ..Default::default()
+                });
+            }
+        }
+        _ => (),
+    }
+    if candidate.find_path(&path).is_ok() {
+        candidate.remove(&path)?;
+    }
+
+    Ok(())
+}
+
+fn value_not_configured(path: String, value: &str) -> yang3::Error {
+    yang3::Error {
+        msg: Some(format!("value not configured: {}", value)),
+        path: Some(path),
+        ..Default::default()
+    }
+}
+
+// Converts a user-provided leaf or leaf-list value into its canonical form,
+// as defined by the type of the node at the given path.
+fn canonical_value(path: &str, value: &str) -> Result<String, yang3::Error> {
+    let yang_ctx = YANG_CTX.get().unwrap();
+    let mut dtree = DataTree::new(yang_ctx);
+    let dnode = dtree.new_path(path, Some(value), false)?;
+    Ok(dnode
+        .and_then(|dnode| dnode.value_canonical())
+        .unwrap_or_else(|| value.to_owned()))
+}
+
+// Resolves a list key pattern against the candidate configuration, returning
+// the data paths of all matching list entries.
+fn list_entries_matching(
+    candidate: &DataTree<'static>,
+    paths: &[String],
+    snode: &SchemaNode<'_>,
+    pattern: &KeyPattern,
+) -> Result<Vec<String>, yang3::Error> {
+    let mut entries = vec![];
+    for path in paths {
+        for dnode in candidate.find_xpath(path)? {
+            let Some(value) = dnode
+                .list_keys()
+                .find(|key| key.schema().name() == snode.name())
+                .and_then(|key| key.value_canonical())
+            else {
+                continue;
+            };
+            if pattern.matches(&value) {
+                entries.push(format!(
+                    "{}{}",
+                    path,
+                    path_predicate(snode.name(), &value)
+                ));
+            }
+        }
+    }
+
+    if entries.is_empty() {
+        return Err(yang3::Error {
+            msg: Some(format!("no list entries matching: {}", pattern)),
+            ..Default::default()
+        });
+    }
+
+    Ok(entries)
+}
+
+// Builds a path predicate comparing a node with the given value, such as a
+// list key or "." for leaf-list entries. XPath literals can't contain escaped
+// quotes, so the value is delimited by whichever quote it doesn't contain.
+pub(crate) fn path_predicate(name: &str, value: &str) -> String {
+    if value.contains('\'') {
+        format!("[{}=\"{}\"]", name, value)
+    } else {
+        format!("[{}='{}']", name, value)
+    }
+}
+
+// Writes a file that is readable only by its owner, as it may contain
+// sensitive data (e.g. passwords).
+fn write_private_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+
+    // The mode is only applied when the file is created.
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    file.write_all(data)
+}
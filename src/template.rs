@@ -0,0 +1,178 @@
+//
+// Copyright (c) The Holo Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+// Template variables, indexed by name.
+pub type TemplateVars = BTreeMap<String, String>;
+
+// ===== global functions =====
+
+// Returns the names of all templates available in the templates directory.
+pub(crate) fn list() -> Vec<String> {
+    let Some(dir) = templates_dir() else {
+        return vec![];
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut names = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+// Reads the contents of the given template.
+pub(crate) fn read(name: &str) -> Result<String, String> {
+    if name.is_empty() || name.starts_with('.') || name.contains('/') {
+        return Err(format!("invalid template name: {}", name));
+    }
+    let dir = templates_dir()
+        .ok_or_else(|| "failed to find the templates directory".to_owned())?;
+    std::fs::read_to_string(dir.join(name))
+        .map_err(|error| format!("failed to read template {}: {}", name, error))
+}
+
+// Parses a list of "var=value" assignments.
+pub(crate) fn parse_vars(line: &str) -> Result<TemplateVars, String> {
+    line.split_whitespace()
+        .map(|assignment| match assignment.split_once('=') {
+            Some((var, value)) if is_var_name(var) => {
+                Ok((var.to_owned(), value.to_owned()))
+            }
+            _ => Err(format!("invalid variable assignment: {}", assignment)),
+        })
+        .collect()
+}
+
+// Replaces all "$var" and "${var}" placeholders with the values of the
+// corresponding variables.
+pub(crate) fn expand(
+    data: &str,
+    vars: &TemplateVars,
+) -> Result<String, String> {
+    let mut output = String::with_capacity(data.len());
+    let mut rest = data;
+    while let Some(index) = rest.find('$') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+
+        // Get variable name.
+        let (var, len) = match rest.strip_prefix('{') {
+            Some(braced) => {
+                let end = braced
+                    .find('}')
+                    .ok_or_else(|| "unterminated variable name".to_owned())?;
+                (&braced[..end], end + 2)
+            }
+            None => {
+                let end =
+                    rest.find(|c: char| !is_var_char(c)).unwrap_or(rest.len());
+                (&rest[..end], end)
+            }
+        };
+
+        // A lone "$" is kept as is.
+        if var.is_empty() {
+            output.push('$');
+            continue;
+        }
+
+        let value = vars
+            .get(var)
+            .ok_or_else(|| format!("undefined variable: ${}", var))?;
+        output.push_str(value);
+        rest = &rest[len..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+// ===== helper functions =====
+
+fn templates_dir() -> Option<PathBuf> {
+    crate::config_dir().map(|dir| dir.join("templates"))
+}
+
+fn is_var_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+fn is_var_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(is_var_char)
+}
+
+// ===== unit tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::string_pairs;
+
+    #[test]
+    fn parse_assignments() {
+        assert_eq!(
+            parse_vars("ifname=eth0  mtu=9000 desc=").unwrap(),
+            TemplateVars::from_iter(string_pairs(&[
+                ("ifname", "eth0"),
+                ("mtu", "9000"),
+                ("desc", "")
+            ]))
+        );
+        assert_eq!(parse_vars("").unwrap(), TemplateVars::new());
+        assert_eq!(
+            parse_vars("a=b=c").unwrap(),
+            TemplateVars::from_iter(string_pairs(&[("a", "b=c")]))
+        );
+        assert!(parse_vars("ifname").is_err());
+        assert!(parse_vars("=eth0").is_err());
+        assert!(parse_vars("if.name=eth0").is_err());
+    }
+
+    #[test]
+    fn expand_placeholders() {
+        let vars = TemplateVars::from_iter(string_pairs(&[
+            ("ifname", "eth0"),
+            ("unit", "10"),
+        ]));
+        assert_eq!(
+            expand("interface $ifname.${unit}\n", &vars).unwrap(),
+            "interface eth0.10\n"
+        );
+        assert_eq!(
+            expand("${ifname}_${unit}$unit", &vars).unwrap(),
+            "eth0_1010"
+        );
+        assert_eq!(expand("no variables", &vars).unwrap(), "no variables");
+    }
+
+    #[test]
+    fn expand_lone_dollar() {
+        let vars = TemplateVars::new();
+        assert_eq!(expand("cost $ 10", &vars).unwrap(), "cost $ 10");
+        assert_eq!(expand("price$", &vars).unwrap(), "price$");
+    }
+
+    #[test]
+    fn expand_errors() {
+        let vars = TemplateVars::from_iter(string_pairs(&[("ifname", "eth0")]));
+        assert_eq!(
+            expand("interface $mtu", &vars),
+            Err("undefined variable: $mtu".to_owned())
+        );
+        assert_eq!(
+            expand("interface ${ifname", &vars),
+            Err("unterminated variable name".to_owned())
+        );
+    }
+}
@@ -7,50 +7,108 @@
 use std::borrow::Cow;
 use std::sync::{Arc, Mutex};
 
+use chrono::{DateTime, Local};
 use indextree::NodeId;
 use itertools::Itertools;
+use nu_ansi_term::{Color, Style};
 use reedline::{
-    ColumnarMenu, Completer, FileBackedHistory, KeyCode, KeyModifiers,
+    ColumnarMenu, Completer, EditCommand, Emacs, FileBackedHistory,
+    Highlighter, Hinter, History, KeyCode, KeyModifiers, Keybindings,
     MenuBuilder, Prompt, PromptEditMode, PromptHistorySearch,
-    PromptHistorySearchStatus, Reedline, ReedlineEvent, ReedlineMenu, Span,
-    Suggestion, Vi,
+    PromptHistorySearchStatus, Reedline, ReedlineEvent, ReedlineMenu,
+    SearchQuery, Span, StyledText, Suggestion, Vi,
 };
 
 use crate::error::ParserError;
 use crate::parser::{self, ParsedCommand};
+use crate::session::Session;
 use crate::token::{Commands, TokenKind};
+use crate::user_config::{EditMode, Settings};
 use crate::Cli;
 
 static DEFAULT_PROMPT_INDICATOR: &str = "# ";
 static DEFAULT_MULTILINE_INDICATOR: &str = "::: ";
 static DEFAULT_HISTORY_SIZE: usize = 1000;
-static DEFAULT_HISTORY_FILENAME: &str = "history.txt";
+static DEFAULT_HINT_HISTORY_ENTRIES: i64 = 100;
+static DEFAULT_TERMINAL_SIZE: (usize, usize) = (80, 24);
+
+// Host command used to request help for the current input line, without
+// changing it.
+pub static HELP_REQUEST: &str = "\0help";
 
 #[derive(Clone)]
-pub struct CliPrompt(String);
+pub struct CliPrompt {
+    banner: Option<String>,
+    left: String,
+    dirty: bool,
+    confirm_deadline: Option<DateTime<Local>>,
+    address: String,
+}
 
 #[derive(Clone)]
 pub struct CliCompleter(Arc<Mutex<Cli>>);
 
+#[derive(Clone)]
+pub struct CliHighlighter(Arc<Mutex<Cli>>);
+
+pub struct CliHinter {
+    cli: Arc<Mutex<Cli>>,
+    current_hint: String,
+}
+
 // ===== impl CliPrompt =====
 
 impl CliPrompt {
-    pub(crate) fn new(string: String) -> Self {
-        Self(string)
+    pub(crate) fn new(session: &Session) -> Self {
+        let mut prompt = Self {
+            banner: None,
+            left: String::new(),
+            dirty: false,
+            confirm_deadline: None,
+            address: String::new(),
+        };
+        prompt.update(session);
+        prompt
     }
 
-    pub(crate) fn update(&mut self, string: String) {
-        self.0 = string;
+    pub(crate) fn update(&mut self, session: &Session) {
+        self.banner = session.edit_banner();
+        self.left = session.prompt();
+        self.dirty = session.candidate_dirty();
+        self.confirm_deadline = session.commit_confirm_deadline();
+        self.address = session.settings().address().to_owned();
     }
 }
 
 impl Prompt for CliPrompt {
     fn render_prompt_left(&self) -> Cow<'_, str> {
-        Cow::Owned(self.0.clone())
+        // The configuration node banner is displayed in a separate line.
+        match &self.banner {
+            Some(banner) => Cow::Owned(format!("{}\n{}", banner, self.left)),
+            None => Cow::Borrowed(&self.left),
+        }
     }
 
     fn render_prompt_right(&self) -> Cow<'_, str> {
-        Cow::Borrowed("")
+        let mut parts = vec![];
+
+        // Uncommitted changes.
+        if self.dirty {
+            parts.push("*".to_owned());
+        }
+
+        // Pending confirmed commit.
+        let now = Local::now();
+        if let Some(indicator) = self
+            .confirm_deadline
+            .and_then(|deadline| confirm_indicator(deadline, now))
+        {
+            parts.push(indicator);
+        }
+
+        parts.push(self.address.clone());
+        parts.push(now.format("%H:%M:%S").to_string());
+        Cow::Owned(parts.join(" "))
     }
 
     fn render_prompt_indicator(
@@ -86,38 +144,8 @@ impl Completer for CliCompleter {
         let cli = self.0.lock().unwrap();
 
         let last_word = line.split_whitespace().last().unwrap_or(line);
-        let partial = line
-            .chars()
-            .last()
-            .map(|c| !c.is_whitespace())
-            .unwrap_or(false);
-
-        let wd_token_id = cli.session.mode().token(&cli.commands);
-        let completions = match parser::parse_command_try(
-            &cli.session,
-            &cli.commands,
-            wd_token_id,
-            line,
-        ) {
-            Ok(ParsedCommand { token_id, .. })
-            | Err(ParserError::Incomplete(token_id)) => {
-                if partial {
-                    complete_add_token(
-                        &cli.commands,
-                        token_id,
-                        partial,
-                        last_word,
-                    )
-                } else {
-                    let token_ids = token_id.children(&cli.commands.arena);
-                    complete_add_tokens(&cli.commands, partial, token_ids)
-                }
-            }
-            Err(ParserError::Ambiguous(token_ids)) => {
-                complete_add_tokens(&cli.commands, partial, token_ids)
-            }
-            _ => vec![],
-        };
+        let partial = is_partial(line);
+        let (completions, _) = complete_line(&cli, line);
 
         completions
             .into_iter()
@@ -136,28 +164,332 @@ impl Completer for CliCompleter {
     }
 }
 
+// ===== impl CliHighlighter =====
+
+impl Highlighter for CliHighlighter {
+    fn highlight(&self, line: &str, _cursor: usize) -> StyledText {
+        let cli = self.0.lock().unwrap();
+        let mut styled = StyledText::new();
+
+        // Check whether the line forms an incomplete command.
+        let incomplete = matches!(
+            parser::parse_command_relaxed(&cli.session, &cli.commands, line),
+            Err(ParserError::Incomplete(_))
+        );
+
+        // Style each word according to what it matches, keeping the
+        // whitespaces between them unstyled.
+        let mut invalid = false;
+        let mut pos = 0;
+        for (index, word) in line.split_whitespace().enumerate() {
+            let start = pos + line[pos..].find(word).unwrap();
+            let end = start + word.len();
+            styled.push((Style::new(), line[pos..start].to_owned()));
+
+            let style = if invalid {
+                Style::new().fg(Color::Red)
+            } else {
+                match parser::parse_command_relaxed(
+                    &cli.session,
+                    &cli.commands,
+                    &line[..end],
+                ) {
+                    Ok(ParsedCommand { token_id, .. })
+                    | Err(ParserError::Incomplete(token_id)) => {
+                        let token = cli.commands.get_token(token_id);
+                        if index == 0 && token.name == "no" {
+                            Style::new().fg(Color::Magenta).bold()
+                        } else if token.kind != TokenKind::Word {
+                            Style::new().fg(Color::Cyan)
+                        } else if incomplete {
+                            Style::new().fg(Color::Yellow)
+                        } else {
+                            Style::new().fg(Color::Green)
+                        }
+                    }
+                    Err(ParserError::NoMatch) => {
+                        // All remaining words are invalid as well.
+                        invalid = true;
+                        Style::new().fg(Color::Red)
+                    }
+                    Err(_) => Style::new(),
+                }
+            };
+            styled.push((style, word.to_owned()));
+            pos = end;
+        }
+        styled.push((Style::new(), line[pos..].to_owned()));
+
+        styled
+    }
+}
+
+// ===== impl CliHinter =====
+
+impl CliHinter {
+    fn new(cli: Arc<Mutex<Cli>>) -> Self {
+        Self {
+            cli,
+            current_hint: String::new(),
+        }
+    }
+
+    // Returns the remainder of the most recent history entry that starts with
+    // the given line and is a valid command in the current mode.
+    fn hint_from_history(
+        cli: &Cli,
+        line: &str,
+        history: &dyn History,
+    ) -> Option<String> {
+        let query = SearchQuery {
+            limit: Some(DEFAULT_HINT_HISTORY_ENTRIES),
+            ..SearchQuery::last_with_prefix(line.to_owned(), None)
+        };
+        history
+            .search(query)
+            .ok()?
+            .into_iter()
+            .map(|entry| entry.command_line)
+            .filter(|command| command.len() > line.len())
+            .find(|command| {
+                parser::parse_command_relaxed(
+                    &cli.session,
+                    &cli.commands,
+                    command,
+                )
+                .is_ok()
+            })
+            .map(|command| command[line.len()..].to_owned())
+    }
+
+    // Returns the rest of the command when there's a single way to continue
+    // it according to the command tree.
+    fn hint_from_commands(cli: &Cli, line: &str) -> Option<String> {
+        let commands = &cli.commands;
+        let mut token_id =
+            match parser::parse_command_relaxed(&cli.session, commands, line) {
+                Ok(ParsedCommand { token_id, .. })
+                | Err(ParserError::Incomplete(token_id)) => token_id,
+                _ => return None,
+            };
+
+        // Complete the last word if it's a keyword prefix.
+        let mut hint = String::new();
+        if is_partial(line) {
+            let token = commands.get_token(token_id);
+            if token.kind != TokenKind::Word {
+                return None;
+            }
+            let word = line.split_whitespace().last().unwrap_or_default();
+            hint.push_str(token.name.get(word.len()..).unwrap_or_default());
+        }
+
+        // Follow the command tree while there's a single keyword to choose.
+        loop {
+            if commands.get_token(token_id).action.is_some() {
+                break;
+            }
+            let mut children = token_id.children(&commands.arena);
+            let (Some(child_id), None) = (children.next(), children.next())
+            else {
+                break;
+            };
+            let child = commands.get_token(child_id);
+            if child.kind != TokenKind::Word {
+                break;
+            }
+            if !hint.is_empty() || is_partial(line) {
+                hint.push(' ');
+            }
+            hint.push_str(&child.name);
+            token_id = child_id;
+        }
+
+        (!hint.is_empty()).then_some(hint)
+    }
+}
+
+impl Hinter for CliHinter {
+    fn handle(
+        &mut self,
+        line: &str,
+        _pos: usize,
+        history: &dyn History,
+        use_ansi_coloring: bool,
+        _cwd: &str,
+    ) -> String {
+        self.current_hint = if line.trim().is_empty() {
+            String::new()
+        } else {
+            let cli = self.cli.lock().unwrap();
+            CliHinter::hint_from_history(&cli, line, history)
+                .or_else(|| CliHinter::hint_from_commands(&cli, line))
+                .unwrap_or_default()
+        };
+
+        if use_ansi_coloring && !self.current_hint.is_empty() {
+            Style::new()
+                .fg(Color::DarkGray)
+                .paint(&self.current_hint)
+                .to_string()
+        } else {
+            self.current_hint.clone()
+        }
+    }
+
+    fn complete_hint(&self) -> String {
+        self.current_hint.clone()
+    }
+
+    fn next_hint_token(&self) -> String {
+        // Leading whitespaces followed by the next word.
+        let start = self
+            .current_hint
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(self.current_hint.len());
+        let end = self.current_hint[start..]
+            .find(char::is_whitespace)
+            .map_or(self.current_hint.len(), |end| start + end);
+        self.current_hint[..end].to_owned()
+    }
+}
+
 // ===== global functions =====
 
-pub(crate) fn reedline_init(
-    cli: Arc<Mutex<Cli>>,
-    use_ansi_coloring: bool,
-) -> Reedline {
-    let history = Box::new(
-        FileBackedHistory::with_file(
-            DEFAULT_HISTORY_SIZE,
-            DEFAULT_HISTORY_FILENAME.into(),
-        )
-        .expect("Error configuring history with file"),
-    );
+// Prints the possible next tokens along with their help text, in the style of
+// traditional router CLIs.
+pub(crate) fn print_help(cli: &Cli, line: &str) {
+    let (mut completions, complete) = complete_line(cli, line);
+    if complete && !is_partial(line) {
+        completions.push(("<cr>".to_owned(), None));
+    }
+
+    println!();
+    if completions.is_empty() {
+        println!("% unknown command");
+        return;
+    }
+    let width = completions
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or_default();
+    for (name, help) in completions {
+        println!("  {:width$}  {}", name, help.unwrap_or_default());
+    }
+}
+
+pub(crate) fn reedline_init(cli: Arc<Mutex<Cli>>) -> Reedline {
+    let (settings, history) = {
+        let cli = cli.lock().unwrap();
+        (cli.session.settings().clone(), history_init(&cli.session))
+    };
+    let highlighter = Box::new(CliHighlighter(cli.clone()));
+    let hinter = Box::new(CliHinter::new(cli.clone()));
     let completer = Box::new(CliCompleter(cli));
     let completion_menu =
         Box::new(ColumnarMenu::default().with_name("completion_menu"));
 
-    let mut insert_keybindings = reedline::default_vi_insert_keybindings();
-    let normal_keybindings = reedline::default_vi_normal_keybindings();
+    let edit_mode: Box<dyn reedline::EditMode> = match settings.edit_mode() {
+        EditMode::Emacs => {
+            let mut keybindings = reedline::default_emacs_keybindings();
+            add_keybindings(&mut keybindings, &settings);
+            Box::new(Emacs::new(keybindings))
+        }
+        EditMode::Vi => {
+            let mut insert_keybindings =
+                reedline::default_vi_insert_keybindings();
+            let normal_keybindings = reedline::default_vi_normal_keybindings();
+            add_keybindings(&mut insert_keybindings, &settings);
+            Box::new(Vi::new(insert_keybindings, normal_keybindings))
+        }
+    };
+    let mut le = Reedline::create()
+        .with_history(history)
+        .with_ansi_colors(settings.colors())
+        .with_completer(completer)
+        .with_quick_completions(true)
+        .with_partial_completions(true)
+        .with_edit_mode(edit_mode)
+        .with_hinter(hinter)
+        .with_menu(ReedlineMenu::EngineCompleter(completion_menu));
+
+    // Highlight the command line as it's typed.
+    if settings.colors() {
+        le = le.with_highlighter(highlighter);
+    }
+
+    le
+}
 
+// Creates the command history of the current CLI mode.
+pub(crate) fn history_init(session: &Session) -> Box<dyn History> {
+    let path = session.history_path(session.mode().is_configure());
+    let history = match path {
+        Some(path) => FileBackedHistory::with_file(DEFAULT_HISTORY_SIZE, path)
+            .or_else(|error| {
+                eprintln!("% failed to open history file: {}", error);
+                FileBackedHistory::new(DEFAULT_HISTORY_SIZE)
+            }),
+        None => FileBackedHistory::new(DEFAULT_HISTORY_SIZE),
+    };
+    Box::new(history.expect("Error configuring history"))
+}
+
+// Returns the terminal size as (columns, rows), falling back to the default
+// size when it can't be determined.
+pub(crate) fn terminal_size() -> (usize, usize) {
+    match crossterm::terminal::size() {
+        Ok((columns, rows)) if columns > 0 && rows > 0 => {
+            (columns as usize, rows as usize)
+        }
+        _ => DEFAULT_TERMINAL_SIZE,
+    }
+}
+
+// Returns the number of columns taken by the given text, ignoring ANSI escape
+// sequences.
+pub(crate) fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the CSI sequence up to its final byte.
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        width += 1;
+    }
+    width
+}
+
+// Adds the CLI-specific key bindings, followed by the user-defined ones.
+// Formats the time at which a confirmed commit will be rolled back, along
+// with the time left. Returns None once the deadline has passed.
+fn confirm_indicator(
+    deadline: DateTime<Local>,
+    now: DateTime<Local>,
+) -> Option<String> {
+    let secs = (deadline - now).num_seconds();
+    (secs > 0).then(|| {
+        format!(
+            "confirm by {} ({:02}:{:02})",
+            deadline.format("%H:%M:%S"),
+            secs / 60,
+            secs % 60
+        )
+    })
+}
+
+fn add_keybindings(keybindings: &mut Keybindings, settings: &Settings) {
     // <Tab> as completion trigger
-    insert_keybindings.add_binding(
+    keybindings.add_binding(
         KeyModifiers::NONE,
         KeyCode::Tab,
         ReedlineEvent::UntilFound(vec![
@@ -166,30 +498,176 @@ pub(crate) fn reedline_init(
         ]),
     );
 
-    // <?> as completion trigger
-    insert_keybindings.add_binding(
+    // <?> as help trigger, leaving the input line untouched
+    keybindings.add_binding(
         KeyModifiers::NONE,
         KeyCode::Char('?'),
-        ReedlineEvent::UntilFound(vec![
-            ReedlineEvent::Menu("completion_menu".to_string()),
-            ReedlineEvent::MenuNext,
-        ]),
+        ReedlineEvent::ExecuteHostCommand(HELP_REQUEST.to_owned()),
+    );
+
+    // <Alt-?> inserts a literal question mark
+    keybindings.add_binding(
+        KeyModifiers::ALT,
+        KeyCode::Char('?'),
+        ReedlineEvent::Edit(vec![EditCommand::InsertChar('?')]),
     );
-    insert_keybindings.add_binding(
+    keybindings.add_binding(
         KeyModifiers::CONTROL,
         KeyCode::Char('z'),
         ReedlineEvent::ExecuteHostCommand("end".to_owned()),
     );
 
-    let edit_mode = Box::new(Vi::new(insert_keybindings, normal_keybindings));
-    Reedline::create()
-        .with_history(history)
-        .with_ansi_colors(use_ansi_coloring)
-        .with_completer(completer)
-        .with_quick_completions(true)
-        .with_partial_completions(true)
-        .with_edit_mode(edit_mode)
-        .with_menu(ReedlineMenu::EngineCompleter(completion_menu))
+    for (key, action) in &settings.keybindings {
+        let Some((modifiers, code)) = parse_key(key) else {
+            eprintln!("% ignoring invalid key binding: {}", key);
+            continue;
+        };
+        let Some(event) = parse_key_action(action) else {
+            eprintln!("% ignoring invalid key binding action: {}", action);
+            continue;
+        };
+        keybindings.add_binding(modifiers, code, event);
+    }
+}
+
+// Parses a key combination such as "ctrl-l", "alt-b" or "f1".
+fn parse_key(key: &str) -> Option<(KeyModifiers, KeyCode)> {
+    let key = key.to_lowercase();
+    let mut modifiers = KeyModifiers::NONE;
+    let mut name = key.as_str();
+    loop {
+        if let Some(rest) = name.strip_prefix("ctrl-") {
+            modifiers |= KeyModifiers::CONTROL;
+            name = rest;
+        } else if let Some(rest) = name.strip_prefix("alt-") {
+            modifiers |= KeyModifiers::ALT;
+            name = rest;
+        } else if let Some(rest) = name.strip_prefix("shift-") {
+            modifiers |= KeyModifiers::SHIFT;
+            name = rest;
+        } else {
+            break;
+        }
+    }
+
+    let code = match name {
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "down" => KeyCode::Down,
+        "end" => KeyCode::End,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "home" => KeyCode::Home,
+        "left" => KeyCode::Left,
+        "pagedown" => KeyCode::PageDown,
+        "pageup" => KeyCode::PageUp,
+        "right" => KeyCode::Right,
+        "space" => KeyCode::Char(' '),
+        "tab" => KeyCode::Tab,
+        "up" => KeyCode::Up,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                (Some('f'), Some(_)) => KeyCode::F(name[1..].parse().ok()?),
+                _ => return None,
+            }
+        }
+    };
+
+    Some((modifiers, code))
+}
+
+// Parses a key binding action. Actions starting with "command:" execute the
+// given CLI command.
+fn parse_key_action(action: &str) -> Option<ReedlineEvent> {
+    if let Some(command) = action.strip_prefix("command:") {
+        return Some(ReedlineEvent::ExecuteHostCommand(
+            command.trim().to_owned(),
+        ));
+    }
+
+    let edit = |command| Some(ReedlineEvent::Edit(vec![command]));
+    match action {
+        "backward-word" => edit(EditCommand::MoveWordLeft { select: false }),
+        "beginning-of-line" => {
+            edit(EditCommand::MoveToLineStart { select: false })
+        }
+        "clear-screen" => Some(ReedlineEvent::ClearScreen),
+        "complete" => Some(ReedlineEvent::UntilFound(vec![
+            ReedlineEvent::Menu("completion_menu".to_string()),
+            ReedlineEvent::MenuNext,
+        ])),
+        "end-of-line" => edit(EditCommand::MoveToLineEnd { select: false }),
+        "forward-word" => edit(EditCommand::MoveWordRight { select: false }),
+        "kill-line" => edit(EditCommand::CutToLineEnd),
+        "kill-whole-line" => edit(EditCommand::CutCurrentLine),
+        "kill-word" => edit(EditCommand::CutWordRight),
+        "backward-kill-word" => edit(EditCommand::CutWordLeft),
+        "next-history" => Some(ReedlineEvent::NextHistory),
+        "previous-history" => Some(ReedlineEvent::PreviousHistory),
+        "redo" => edit(EditCommand::Redo),
+        "search-history" => Some(ReedlineEvent::SearchHistory),
+        "undo" => edit(EditCommand::Undo),
+        "unix-line-discard" => edit(EditCommand::CutFromLineStart),
+        _ => None,
+    }
+}
+
+fn is_partial(line: &str) -> bool {
+    line.chars()
+        .last()
+        .map(|c| !c.is_whitespace())
+        .unwrap_or(false)
+}
+
+// Returns the possible completions for the given input line, along with
+// whether it already forms a complete command.
+fn complete_line(
+    cli: &Cli,
+    line: &str,
+) -> (Vec<(String, Option<String>)>, bool) {
+    let last_word = line.split_whitespace().last().unwrap_or(line);
+    let partial = is_partial(line);
+
+    let wd_token_id = cli.session.mode().token(&cli.commands);
+    match parser::parse_command_try(
+        &cli.session,
+        &cli.commands,
+        wd_token_id,
+        line,
+    ) {
+        Ok(ParsedCommand { token_id, .. }) => {
+            let completions =
+                complete_token(&cli.commands, token_id, partial, last_word);
+            (completions, true)
+        }
+        Err(ParserError::Incomplete(token_id)) => {
+            let completions =
+                complete_token(&cli.commands, token_id, partial, last_word);
+            (completions, false)
+        }
+        Err(ParserError::Ambiguous(token_ids)) => {
+            let completions =
+                complete_add_tokens(&cli.commands, partial, token_ids);
+            (completions, false)
+        }
+        _ => (vec![], false),
+    }
+}
+
+fn complete_token(
+    commands: &Commands,
+    token_id: NodeId,
+    partial: bool,
+    word: &str,
+) -> Vec<(String, Option<String>)> {
+    if partial {
+        complete_add_token(commands, token_id, partial, word)
+    } else {
+        let token_ids = token_id.children(&commands.arena);
+        complete_add_tokens(commands, partial, token_ids)
+    }
 }
 
 fn complete_add_token(
@@ -203,7 +681,7 @@ fn complete_add_token(
     let token = commands.get_token(token_id);
     if token.kind == TokenKind::Word && !token.matches(word, true) {
         completions.push((token.name.clone(), token.help.clone()));
-    } else if token.kind == TokenKind::String && !partial {
+    } else if token.kind != TokenKind::Word && !partial {
         completions.push((token.name.to_uppercase(), token.help.clone()));
     } else if token.kind == TokenKind::Word && token.matches(word, false) {
         completions.push((token.name.to_lowercase(), token.help.clone()));
@@ -223,7 +701,7 @@ fn complete_add_tokens(
             let token = commands.get_token(token_id);
             if token.kind == TokenKind::Word {
                 Some((token.name.clone(), token.help.clone()))
-            } else if token.kind == TokenKind::String && !partial {
+            } else if token.kind != TokenKind::Word && !partial {
                 Some((token.name.to_uppercase(), token.help.clone()))
             } else {
                 None
@@ -232,3 +710,36 @@ fn complete_add_tokens(
         .sorted()
         .collect()
 }
+
+// ===== unit tests =====
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    #[test]
+    fn confirm_countdown() {
+        let deadline = Local::now();
+        assert_eq!(
+            confirm_indicator(deadline, deadline - TimeDelta::seconds(272)),
+            Some(format!(
+                "confirm by {} (04:32)",
+                deadline.format("%H:%M:%S")
+            ))
+        );
+        assert_eq!(
+            confirm_indicator(deadline, deadline - TimeDelta::minutes(120)),
+            Some(format!(
+                "confirm by {} (120:00)",
+                deadline.format("%H:%M:%S")
+            ))
+        );
+        assert_eq!(confirm_indicator(deadline, deadline), None);
+        assert_eq!(
+            confirm_indicator(deadline, deadline + TimeDelta::seconds(1)),
+            None
+        );
+    }
+}
@@ -0,0 +1,16 @@
+//
+// Copyright (c) The Holo Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+// Helpers shared by the unit tests.
+
+// Converts a list of string pairs, such as command arguments or template
+// variables, into owned strings.
+pub(crate) fn string_pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
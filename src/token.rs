@@ -7,9 +7,11 @@
 use indextree::{Arena, NodeId};
 use yang3::schema::SchemaNode;
 
+use crate::error::Error;
 use crate::parser::ParsedArgs;
 use crate::session::Session;
-use crate::{token_xml, token_yang};
+use crate::user_config::UserConfig;
+use crate::{token_user, token_xml, token_yang};
 
 pub struct Commands {
     pub arena: Arena<Option<Token>>,
@@ -26,24 +28,29 @@ pub struct Token {
     pub argument: Option<String>,
     pub action: Option<Action>,
     pub node_update: bool,
+    pub snode: Option<SchemaNode<'static>>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum TokenKind {
     Word,
     String,
+    // Takes the rest of the input line as its argument.
+    Line,
 }
 
 pub enum Action {
     Callback(Callback),
     ConfigEdit(SchemaNode<'static>),
+    // Series of commands defined by the user (aliases and macros).
+    Macro(Vec<String>),
 }
 
 type Callback = fn(
     commands: &Commands,
     session: &mut Session,
     args: ParsedArgs,
-) -> Result<bool, String>;
+) -> Result<bool, Error>;
 
 // ===== impl Commands =====
 
@@ -64,9 +71,10 @@ impl Commands {
         }
     }
 
-    pub(crate) fn gen_cmds(&mut self) {
+    pub(crate) fn gen_cmds(&mut self, user_config: &UserConfig) {
         token_yang::gen_cmds(self);
         token_xml::gen_cmds(self);
+        token_user::gen_cmds(self, user_config);
     }
 
     pub(crate) fn add_token(&mut self, parent: NodeId, token: Token) -> NodeId {
@@ -94,6 +102,7 @@ impl Token {
         argument: Option<S>,
         action: Option<Action>,
         node_update: bool,
+        snode: Option<SchemaNode<'static>>,
     ) -> Token {
         Token {
             name: name.into(),
@@ -102,11 +111,12 @@ impl Token {
             argument: argument.map(|s| s.into()),
             action,
             node_update,
+            snode,
         }
     }
 
     pub(crate) fn matches(&self, word: &str, exact: bool) -> bool {
-        if self.kind == TokenKind::String {
+        if self.kind != TokenKind::Word {
             // TODO: custom match per token type.
             true
         } else if exact {
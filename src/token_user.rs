@@ -0,0 +1,77 @@
+//
+// Copyright (c) The Holo Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+use indextree::NodeId;
+
+use crate::token::{Action, Commands, Token, TokenKind};
+use crate::user_config::UserConfig;
+
+pub(crate) fn gen_cmds(commands: &mut Commands, config: &UserConfig) {
+    // Add aliases. Any extra words are appended to the aliased command.
+    for (name, command) in &config.aliases {
+        let help = format!("Alias for \"{}\".", command);
+        let action = || Action::Macro(vec![command.clone()]);
+        let Some(token_id) = add_token(commands, name, help, action()) else {
+            continue;
+        };
+        let token = Token::new(
+            "arguments",
+            Some("Arguments appended to the aliased command."),
+            TokenKind::Line,
+            Some("arguments"),
+            Some(action()),
+            false,
+            None,
+        );
+        commands.add_token(token_id, token);
+    }
+
+    // Add macros.
+    for (name, user_macro) in &config.macros {
+        let help = user_macro
+            .help
+            .clone()
+            .unwrap_or_else(|| "User-defined macro.".to_owned());
+        let action = Action::Macro(user_macro.commands.clone());
+        add_token(commands, name, help, action);
+    }
+}
+
+// Adds a top-level exec token, unless it conflicts with an existing command.
+fn add_token(
+    commands: &mut Commands,
+    name: &str,
+    help: String,
+    action: Action,
+) -> Option<NodeId> {
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        eprintln!("% ignoring invalid alias or macro name: \"{}\"", name);
+        return None;
+    }
+    if commands
+        .exec_root
+        .children(&commands.arena)
+        .filter_map(|token_id| commands.get_opt_token(token_id))
+        .any(|token| token.name == name)
+    {
+        eprintln!(
+            "% ignoring alias or macro \"{}\": conflicts with an existing command",
+            name
+        );
+        return None;
+    }
+
+    let token = Token::new(
+        name.to_owned(),
+        Some(help),
+        TokenKind::Word,
+        None,
+        Some(action),
+        false,
+        None,
+    );
+    Some(commands.add_token(commands.exec_root, token))
+}
@@ -77,10 +77,23 @@ fn parse_tag_token(
         "cmd_end" => internal_commands::cmd_end,
         "cmd_pwd" => internal_commands::cmd_pwd,
         "cmd_discard" => internal_commands::cmd_discard,
+        "cmd_load" => internal_commands::cmd_load,
+        "cmd_undo" => internal_commands::cmd_undo,
+        "cmd_redo" => internal_commands::cmd_redo,
+        "cmd_apply_template" => internal_commands::cmd_apply_template,
+        "cmd_checkpoint" => internal_commands::cmd_checkpoint,
+        "cmd_restore" => internal_commands::cmd_restore,
         "cmd_commit" => internal_commands::cmd_commit,
         "cmd_validate" => internal_commands::cmd_validate,
+        "cmd_save_config" => internal_commands::cmd_save_config,
+        "cmd_set_cli" => internal_commands::cmd_set_cli,
+        "cmd_show_cli_settings" => internal_commands::cmd_show_cli_settings,
         "cmd_show_config" => internal_commands::cmd_show_config,
         "cmd_show_config_changes" => internal_commands::cmd_show_config_changes,
+        "cmd_show_template" => internal_commands::cmd_show_template,
+        "cmd_show_checkpoints" => internal_commands::cmd_show_checkpoints,
+        "cmd_show_history" => internal_commands::cmd_show_history,
+        "cmd_show_history_edits" => internal_commands::cmd_show_history_edits,
         "cmd_show_state" => internal_commands::cmd_show_state,
         "cmd_show_yang_modules" => internal_commands::cmd_show_yang_modules,
         "cmd_show_isis_interface" => internal_commands::cmd_show_isis_interface,
@@ -136,6 +149,7 @@ fn parse_tag_token(
 
     let kind = match kind {
         Some("string") => TokenKind::String,
+        Some("line") => TokenKind::Line,
         Some(_) => panic!("unknown token kind"),
         None => TokenKind::Word,
     };
@@ -143,7 +157,7 @@ fn parse_tag_token(
     let action = callback.map(|callback| Action::Callback(callback));
 
     // Add new token.
-    let token = Token::new(name, help, kind, argument, action, false);
+    let token = Token::new(name, help, kind, argument, action, false, None);
 
     // Link new token.
     commands.add_token(parent, token)
@@ -15,6 +15,11 @@ use crate::parser::ParsedArgs;
 use crate::token::{Action, Commands, Token, TokenKind};
 use crate::YANG_CTX;
 
+// Help text appended to list keys, documenting the limitations of list key
+// patterns.
+static KEY_PATTERN_HELP: &str = "Patterns like \"eth[0-23]\" or \"eth*\" \
+    edit the matching entries but can't enter them; type \"?\" with Alt-?.";
+
 pub(crate) fn gen_cmds(commands: &mut Commands) {
     // Iterate over top-level YANG nodes.
     let yang_ctx = YANG_CTX.get().unwrap();
@@ -88,13 +93,28 @@ fn add_token(
     is_argument: bool,
 ) {
     let name = snode.name();
-    let help = snode.description();
+    let help = match snode.description() {
+        Some(help) if snode.is_list_key() => {
+            Some(format!("{} {}", help.trim_end(), KEY_PATTERN_HELP))
+        }
+        Some(help) => Some(help.to_owned()),
+        None if snode.is_list_key() => Some(KEY_PATTERN_HELP.to_owned()),
+        None => None,
+    };
     let argument = if is_argument { Some(name) } else { None };
     let action = is_full_command(snode, is_argument)
         .then(|| Action::ConfigEdit(snode.clone()));
     let node_update = snode.kind() == SchemaNodeKind::List;
 
-    let token = Token::new(name, help, kind, argument, action, node_update);
+    let token = Token::new(
+        name,
+        help.as_deref(),
+        kind,
+        argument,
+        action,
+        node_update,
+        Some(snode.clone()),
+    );
     *token_id = commands.add_token(*token_id, token);
     snode_set_token_id(snode, *token_id);
 }
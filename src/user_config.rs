@@ -0,0 +1,301 @@
+//
+// Copyright (c) The Holo Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+// Name of the user configuration file.
+static USER_CONFIG_FILENAME: &str = "config.toml";
+
+// Default settings.
+static DEFAULT_ADDRESS: &str = "http://[::1]:50051";
+static DEFAULT_CONNECT_TIMEOUT: u64 = 5;
+static DEFAULT_REQUEST_TIMEOUT: u64 = 60;
+static DEFAULT_COMMIT_TIMEOUT: u64 = 300;
+
+// Pager command that selects the built-in pager.
+static INTERNAL_PAGER: &str = "internal";
+
+// User configuration, read from "~/.config/holo-cli/config.toml".
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UserConfig {
+    // Command aliases, mapping the alias name to the command it expands to.
+    pub aliases: BTreeMap<String, String>,
+    // Macros, executing a series of commands.
+    pub macros: BTreeMap<String, UserMacro>,
+    // Default settings.
+    pub settings: Settings,
+    // Named settings profiles (e.g. one per device), overriding the default
+    // settings.
+    pub profiles: BTreeMap<String, Settings>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserMacro {
+    pub help: Option<String>,
+    pub commands: Vec<String>,
+}
+
+// CLI settings. Unset options take their default values.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Settings {
+    // Holo daemon address.
+    pub address: Option<String>,
+    // Maximum time to wait for the connection to holod, in seconds.
+    pub connect_timeout: Option<u64>,
+    // Maximum time to wait for each request to holod, in seconds. Zero
+    // disables the timeout.
+    pub request_timeout: Option<u64>,
+    // Maximum time to wait for each commit to holod, in seconds. Zero disables
+    // the timeout.
+    pub commit_timeout: Option<u64>,
+    // Whether long outputs are displayed through a pager.
+    pub pager: Option<bool>,
+    // External pager command line. The built-in pager is used when unset.
+    pub pager_command: Option<String>,
+    // Whether ANSI colors are used.
+    pub colors: Option<bool>,
+    // Line editor mode.
+    pub edit_mode: Option<EditMode>,
+    // Path of the command history file. When unset, histories are kept per
+    // target device under the XDG state directory.
+    pub history_file: Option<PathBuf>,
+    // Additional key bindings, mapping key combinations (e.g. "ctrl-l") to
+    // line editor actions (e.g. "clear-screen").
+    pub keybindings: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EditMode {
+    #[default]
+    Emacs,
+    Vi,
+}
+
+// ===== impl UserConfig =====
+
+impl UserConfig {
+    // Loads the user configuration file, falling back to the default
+    // configuration if it doesn't exist or can't be parsed.
+    pub(crate) fn load() -> UserConfig {
+        let Some(path) = UserConfig::path() else {
+            return UserConfig::default();
+        };
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return UserConfig::default();
+            }
+            Err(error) => {
+                eprintln!("% failed to read {}: {}", path.display(), error);
+                return UserConfig::default();
+            }
+        };
+        match toml::from_str(&data) {
+            Ok(config) => config,
+            Err(error) => {
+                eprintln!("% failed to parse {}: {}", path.display(), error);
+                UserConfig::default()
+            }
+        }
+    }
+
+    // Returns the default settings, overridden by the given profile.
+    pub(crate) fn settings(
+        &self,
+        profile: Option<&str>,
+    ) -> Result<Settings, String> {
+        let mut settings = self.settings.clone();
+        if let Some(profile) = profile {
+            let profile = self
+                .profiles
+                .get(profile)
+                .ok_or_else(|| format!("unknown profile: {}", profile))?;
+            settings.merge(profile);
+        }
+        Ok(settings)
+    }
+
+    fn path() -> Option<PathBuf> {
+        crate::config_dir().map(|dir| dir.join(USER_CONFIG_FILENAME))
+    }
+}
+
+// ===== impl Settings =====
+
+impl Settings {
+    // Overrides the options that are set in `other`.
+    pub(crate) fn merge(&mut self, other: &Settings) {
+        if let Some(address) = &other.address {
+            self.address = Some(address.clone());
+        }
+        if let Some(connect_timeout) = other.connect_timeout {
+            self.connect_timeout = Some(connect_timeout);
+        }
+        if let Some(request_timeout) = other.request_timeout {
+            self.request_timeout = Some(request_timeout);
+        }
+        if let Some(commit_timeout) = other.commit_timeout {
+            self.commit_timeout = Some(commit_timeout);
+        }
+        if let Some(pager) = other.pager {
+            self.pager = Some(pager);
+        }
+        if let Some(pager_command) = &other.pager_command {
+            self.pager_command = Some(pager_command.clone());
+        }
+        if let Some(colors) = other.colors {
+            self.colors = Some(colors);
+        }
+        if let Some(edit_mode) = other.edit_mode {
+            self.edit_mode = Some(edit_mode);
+        }
+        if let Some(history_file) = &other.history_file {
+            self.history_file = Some(history_file.clone());
+        }
+        self.keybindings.extend(
+            other
+                .keybindings
+                .iter()
+                .map(|(key, action)| (key.clone(), action.clone())),
+        );
+    }
+
+    // Changes an option from its textual representation.
+    pub(crate) fn set(
+        &mut self,
+        option: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        match option {
+            "address" => self.address = Some(value.to_owned()),
+            "pager" => self.pager = Some(parse_bool(value)?),
+            "pager-command" => {
+                self.pager_command =
+                    (value != INTERNAL_PAGER).then(|| value.to_owned());
+            }
+            "colors" => self.colors = Some(parse_bool(value)?),
+            "edit-mode" => {
+                self.edit_mode = Some(match value {
+                    "emacs" => EditMode::Emacs,
+                    "vi" => EditMode::Vi,
+                    _ => return Err(format!("invalid edit mode: {}", value)),
+                })
+            }
+            "history-file" => self.history_file = Some(value.into()),
+            _ => return Err(format!("unknown option: {}", option)),
+        }
+        Ok(())
+    }
+
+    // Returns all options along with their effective values.
+    pub(crate) fn options(&self) -> Vec<(&'static str, String)> {
+        let mut options = vec![
+            ("address", self.address().to_owned()),
+            (
+                "connect-timeout",
+                format!("{} s", self.connect_timeout().as_secs()),
+            ),
+            (
+                "request-timeout",
+                self.request_timeout()
+                    .map(|timeout| format!("{} s", timeout.as_secs()))
+                    .unwrap_or_else(|| "none".to_owned()),
+            ),
+            (
+                "commit-timeout",
+                self.commit_timeout()
+                    .map(|timeout| format!("{} s", timeout.as_secs()))
+                    .unwrap_or_else(|| "none".to_owned()),
+            ),
+            ("pager", self.pager().to_string()),
+            (
+                "pager-command",
+                self.pager_command().unwrap_or(INTERNAL_PAGER).to_owned(),
+            ),
+            ("colors", self.colors().to_string()),
+            ("edit-mode", self.edit_mode().to_string()),
+            (
+                "history-file",
+                self.history_file()
+                    .map(|file| file.display().to_string())
+                    .unwrap_or_else(|| "per device".to_owned()),
+            ),
+        ];
+        options.extend(self.keybindings.iter().map(|(key, action)| {
+            ("keybinding", format!("{} = {}", key, action))
+        }));
+        options
+    }
+
+    pub(crate) fn address(&self) -> &str {
+        self.address.as_deref().unwrap_or(DEFAULT_ADDRESS)
+    }
+
+    pub(crate) fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+        )
+    }
+
+    pub(crate) fn request_timeout(&self) -> Option<Duration> {
+        let timeout = self.request_timeout.unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+        (timeout != 0).then(|| Duration::from_secs(timeout))
+    }
+
+    pub(crate) fn commit_timeout(&self) -> Option<Duration> {
+        let timeout = self.commit_timeout.unwrap_or(DEFAULT_COMMIT_TIMEOUT);
+        (timeout != 0).then(|| Duration::from_secs(timeout))
+    }
+
+    pub(crate) fn pager(&self) -> bool {
+        self.pager.unwrap_or(true)
+    }
+
+    pub(crate) fn pager_command(&self) -> Option<&str> {
+        self.pager_command.as_deref()
+    }
+
+    pub(crate) fn colors(&self) -> bool {
+        self.colors.unwrap_or(true)
+    }
+
+    pub(crate) fn edit_mode(&self) -> EditMode {
+        self.edit_mode.unwrap_or_default()
+    }
+
+    pub(crate) fn history_file(&self) -> Option<&Path> {
+        self.history_file.as_deref()
+    }
+}
+
+// ===== impl EditMode =====
+
+impl std::fmt::Display for EditMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditMode::Emacs => write!(f, "emacs"),
+            EditMode::Vi => write!(f, "vi"),
+        }
+    }
+}
+
+// ===== helper functions =====
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" | "on" | "yes" => Ok(true),
+        "false" | "off" | "no" => Ok(false),
+        _ => Err(format!("invalid boolean value: {}", value)),
+    }
+}